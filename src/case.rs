@@ -0,0 +1,138 @@
+use crate::ext::{Scope, map_parts, map_scope};
+use crate::i18n::msg;
+use clap::ValueEnum;
+
+/// 大小写转换方式
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaseStyle {
+    /// 全部小写：readme.md
+    #[value(help = msg::value_case_lower())]
+    Lower,
+    /// 全部大写：README.MD
+    #[value(help = msg::value_case_upper())]
+    Upper,
+    /// 每个单词首字母大写，保留原有分隔符：My File Name
+    #[value(help = msg::value_case_title())]
+    Title,
+    /// 小驼峰：myFileName
+    #[value(help = msg::value_case_camel())]
+    Camel,
+    /// 下划线：my_file_name
+    #[value(help = msg::value_case_snake())]
+    Snake,
+    /// 连字符：my-file-name
+    #[value(help = msg::value_case_kebab())]
+    Kebab,
+}
+
+/// 对文件名（不含父目录）按范围执行大小写转换
+///
+/// 隐藏文件开头的 `.` 会被保留。camel、snake、kebab 会去掉单词之间的 `.`，
+/// 因此主名与扩展名分别转换，`.tar.gz` 等复合扩展名的各段也分别转换。
+/// title 作用于整个文件名时只转换主名，扩展名保持原样（`README.md` -> `Readme.md`）。
+pub fn convert(name: &str, style: CaseStyle, scope: Scope) -> String {
+    let rest = name.trim_start_matches('.');
+    let dots = &name[..name.len() - rest.len()];
+    let converted = match (style, scope) {
+        (CaseStyle::Camel | CaseStyle::Snake | CaseStyle::Kebab, Scope::Name) => {
+            map_parts(rest, |stem| apply(stem, style), |ext| apply_ext(ext, style))
+        }
+        (CaseStyle::Title, Scope::Name) => map_parts(rest, title, str::to_string),
+        (CaseStyle::Camel | CaseStyle::Snake | CaseStyle::Kebab, Scope::Ext) => {
+            map_scope(rest, scope, |ext| apply_ext(ext, style))
+        }
+        _ => map_scope(rest, scope, |part| apply(part, style)),
+    };
+    format!("{}{}", dots, converted)
+}
+
+fn apply_ext(ext: &str, style: CaseStyle) -> String {
+    ext.split('.')
+        .map(|part| apply(part, style))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn apply(s: &str, style: CaseStyle) -> String {
+    match style {
+        CaseStyle::Lower => s.to_lowercase(),
+        CaseStyle::Upper => s.to_uppercase(),
+        CaseStyle::Title => title(s),
+        CaseStyle::Camel => {
+            let mut out = String::new();
+            for (i, word) in words(s).iter().enumerate() {
+                if i == 0 {
+                    out.push_str(&word.to_lowercase());
+                } else {
+                    out.push_str(&capitalize(word));
+                }
+            }
+            out
+        }
+        CaseStyle::Snake => join_lower(s, "_"),
+        CaseStyle::Kebab => join_lower(s, "-"),
+    }
+}
+
+fn join_lower(s: &str, separator: &str) -> String {
+    words(s)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// 保留非字母数字字符，把每个字母数字片段首字母大写、其余小写
+fn title(s: &str) -> String {
+    let mut out = String::new();
+    let mut at_word_start = true;
+    for c in s.chars() {
+        if c.is_alphanumeric() {
+            if at_word_start {
+                out.extend(c.to_uppercase());
+            } else {
+                out.extend(c.to_lowercase());
+            }
+            at_word_start = false;
+        } else {
+            out.push(c);
+            at_word_start = true;
+        }
+    }
+    out
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// 按分隔符和大小写边界拆分单词：`myHTTPServer_v2` -> my, HTTP, Server, v2
+fn words(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in s.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<(usize, char)> = part.char_indices().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (idx, c) = chars[i];
+            let prev = chars[i - 1].1;
+            let next_is_lower = chars.get(i + 1).is_some_and(|&(_, n)| n.is_lowercase());
+            // aB 处拆分；ABc 在 B 之前拆分，使连续大写的缩写保持完整
+            if c.is_uppercase() && (prev.is_lowercase() || (prev.is_uppercase() && next_is_lower)) {
+                words.push(&part[start..idx]);
+                start = idx;
+            }
+        }
+        if start < part.len() {
+            words.push(&part[start..]);
+        }
+    }
+    words.retain(|w| !w.is_empty());
+    words
+}
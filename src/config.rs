@@ -0,0 +1,161 @@
+use crate::error::Error;
+use crate::i18n::msg;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// 项目配置文件名，从当前目录向上查找
+const PROJECT_FILE: &str = ".rename-cli.toml";
+
+/// 不能写在预设中的选项：`--lang` 在展开预设之前就已决定界面语言
+const RESERVED: &[&str] = &["preset", "lang"];
+
+/// 配置文件中的一个预设
+///
+/// 除 `description` 和 `pattern` 外，其余键均为长选项名（`set-ext` 或 `set_ext`），
+/// 按在文件中的顺序展开为命令行参数，因此 `replace`、`lower` 等步骤的顺序与文件中一致。
+#[derive(Deserialize, Debug)]
+pub struct Preset {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 命令行中没有给出 <PATTERN> 时使用的筛选模式
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(flatten)]
+    options: toml::Table,
+    /// 定义此预设的配置文件
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+#[derive(Deserialize, Default)]
+struct ConfigFile {
+    #[serde(default)]
+    presets: BTreeMap<String, Preset>,
+}
+
+impl Preset {
+    /// 展开为 `--long=value` 形式的参数，`longs` 为允许在预设中使用的长选项名
+    ///
+    /// `true` 展开为开关，`false` 忽略，数组中的每个元素各展开一次。
+    /// `given` 中的选项已在命令行中给出，整体取代预设中的值（包括 `replace` 等可重复的选项）。
+    pub fn args(&self, name: &str, longs: &[String], given: &[String]) -> Result<Vec<String>, Error> {
+        let mut args = Vec::new();
+        for (key, value) in &self.options {
+            let long = key.replace('_', "-");
+            if RESERVED.contains(&long.as_str()) {
+                return Err(Error::Usage(msg::preset_reserved_option(name, key)));
+            }
+            if !longs.contains(&long) {
+                return Err(Error::Usage(msg::preset_unknown_option(name, key)));
+            }
+            if given.contains(&long) {
+                continue;
+            }
+            let values = match value {
+                toml::Value::Array(items) => items.as_slice(),
+                value => std::slice::from_ref(value),
+            };
+            for value in values {
+                match value {
+                    toml::Value::Boolean(true) => args.push(format!("--{}", long)),
+                    toml::Value::Boolean(false) => {}
+                    toml::Value::String(s) => args.push(format!("--{}={}", long, s)),
+                    toml::Value::Integer(n) => args.push(format!("--{}={}", long, n)),
+                    _ => return Err(Error::Usage(msg::preset_invalid_value(name, key))),
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// 依次读取用户配置和项目配置，同名预设以项目配置为准
+pub fn load() -> Result<BTreeMap<String, Preset>, Error> {
+    let mut presets = BTreeMap::new();
+    for path in config_paths() {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(Error::Usage(msg::config_unreadable(path.display(), e))),
+        };
+        let file: ConfigFile = toml::from_str(&text)
+            .map_err(|e| Error::Usage(msg::config_invalid(path.display(), e.to_string().trim())))?;
+        for (name, mut preset) in file.presets {
+            preset.source = path.clone();
+            presets.insert(name, preset);
+        }
+    }
+    Ok(presets)
+}
+
+/// 用户配置 `$XDG_CONFIG_HOME/rename-cli/config.toml`（或对应平台的配置目录），
+/// 以及当前目录或最近的上级目录中的 `.rename-cli.toml`
+pub fn config_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = dirs::config_dir()
+        .map(|dir| dir.join("rename-cli").join("config.toml"))
+        .into_iter()
+        .collect();
+    if let Ok(cwd) = env::current_dir() {
+        paths.extend(
+            cwd.ancestors()
+                .map(|dir| dir.join(PROJECT_FILE))
+                .find(|path| path.is_file()),
+        );
+    }
+    paths
+}
+
+/// 把 `--preset NAME` 选中的预设展开到其余参数之前，命令行中的选项因此覆盖预设中的值
+///
+/// 需要在解析参数之前调用。返回展开后的参数和预设给出的筛选模式，多个预设按出现顺序叠加。
+pub fn expand(
+    argv: Vec<OsString>,
+    longs: &[String],
+    given: &[String],
+) -> Result<(Vec<OsString>, Option<String>), Error> {
+    let names = scan_long_option(&argv, "--preset");
+    if names.is_empty() {
+        return Ok((argv, None));
+    }
+    let presets = load()?;
+    let mut expanded = Vec::new();
+    let mut pattern = None;
+    for name in &names {
+        let preset = presets
+            .get(name)
+            .ok_or_else(|| Error::Usage(msg::preset_not_found(name)))?;
+        expanded.extend(preset.args(name, longs, given)?.into_iter().map(OsString::from));
+        pattern = preset.pattern.clone().or(pattern);
+    }
+    let mut argv = argv.into_iter();
+    let mut result: Vec<OsString> = argv.next().into_iter().collect();
+    result.extend(expanded);
+    result.extend(argv);
+    Ok((result, pattern))
+}
+
+/// 在解析参数之前扫描原始参数中的 `--long VALUE` 和 `--long=VALUE`，按出现顺序返回各个值
+///
+/// 非 UTF-8 的参数不可能是选项名，作为值时同样忽略，但仍占据一个位置以免错位。
+pub fn scan_long_option(argv: &[OsString], long: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut args = argv.iter().skip(1).map(|arg| arg.to_str());
+    while let Some(arg) = args.next() {
+        let Some(arg) = arg else { continue };
+        if arg == "--" {
+            break;
+        }
+        let value = match arg.strip_prefix(long) {
+            Some("") => args.next().flatten(),
+            Some(rest) => rest.strip_prefix('='),
+            None => None,
+        };
+        values.extend(value.map(str::to_string));
+    }
+    values
+}
@@ -0,0 +1,190 @@
+use colored::*;
+use unicode_width::UnicodeWidthStr;
+
+/// 超过此规模（旧词数 × 新词数）时不再逐词比较，整体视为替换
+const MAX_CELLS: usize = 1 << 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Same,
+    Removed,
+    Added,
+}
+
+/// 旧名称与新名称之间的差异
+///
+/// 先按词（连续的字母数字或单个分隔符）比较，再对被替换的词去掉公共的前后缀，
+/// 这样 `IMG_001` -> `2024-img-001` 不会把日期中的数字拆成碎片，
+/// 而 `photo1` -> `photo2` 只标出变化的那个字符。
+pub struct Diff {
+    ops: Vec<(Op, String)>,
+}
+
+impl Diff {
+    pub fn new(old: &str, new: &str) -> Self {
+        let (old_words, new_words) = (words(old), words(new));
+        let mut ops = Vec::new();
+        if old_words.len() * new_words.len() > MAX_CELLS {
+            push(&mut ops, Op::Removed, old);
+            push(&mut ops, Op::Added, new);
+        } else {
+            for (op, word) in lcs(&old_words, &new_words) {
+                push(&mut ops, op, word);
+            }
+        }
+        Diff { ops: refine(ops) }
+    }
+
+    /// 旧名称，删除的部分高亮；不输出颜色时写作 `[-删除-]`
+    pub fn old(&self) -> String {
+        self.render(Op::Removed, |s| s.red(), |s| s.white().on_red(), ("[-", "-]"))
+    }
+
+    /// 新名称，插入的部分高亮；不输出颜色时写作 `{+插入+}`
+    pub fn new_name(&self) -> String {
+        self.render(Op::Added, |s| s.green(), |s| s.black().on_green(), ("{+", "+}"))
+    }
+
+    /// 旧名称在终端中占用的列数，不输出颜色时包括 `[-`、`-]` 标记，用于对齐
+    pub fn old_width(&self) -> usize {
+        let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+        self.ops
+            .iter()
+            .map(|(op, text)| match op {
+                Op::Same => text.width(),
+                Op::Removed if colorize => text.width(),
+                Op::Removed => text.width() + 4,
+                Op::Added => 0,
+            })
+            .sum()
+    }
+
+    fn render(
+        &self,
+        changed: Op,
+        same: fn(&str) -> ColoredString,
+        highlight: fn(&str) -> ColoredString,
+        (open, close): (&str, &str),
+    ) -> String {
+        let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+        let mut out = String::new();
+        for (op, text) in &self.ops {
+            match *op {
+                Op::Same => out.push_str(&same(text).to_string()),
+                op if op == changed && colorize => out.push_str(&highlight(text).to_string()),
+                op if op == changed => {
+                    out.push_str(open);
+                    out.push_str(text);
+                    out.push_str(close);
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+}
+
+/// 拆分为连续的字母数字和单个其他字符
+fn words(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        if c.is_alphanumeric() {
+            start.get_or_insert(i);
+            continue;
+        }
+        if let Some(begin) = start.take() {
+            words.push(&s[begin..i]);
+        }
+        words.push(&s[i..i + c.len_utf8()]);
+    }
+    if let Some(begin) = start {
+        words.push(&s[begin..]);
+    }
+    words
+}
+
+fn push(ops: &mut Vec<(Op, String)>, op: Op, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match ops.last_mut() {
+        Some((last, existing)) if *last == op => existing.push_str(text),
+        _ => ops.push((op, text.to_string())),
+    }
+}
+
+/// 最长公共子序列，返回逐词的操作序列
+fn lcs<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    // table[i][j] 为 old[i..] 与 new[j..] 的最长公共子序列长度
+    let mut table = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if old[i] == new[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::with_capacity(n + m);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push((Op::Same, old[i]));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            ops.push((Op::Removed, old[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Added, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|&w| (Op::Removed, w)));
+    ops.extend(new[j..].iter().map(|&w| (Op::Added, w)));
+    ops
+}
+
+/// 相邻的删除和插入构成一次替换，把两者公共的前后缀移出高亮范围
+fn refine(ops: Vec<(Op, String)>) -> Vec<(Op, String)> {
+    let mut out = Vec::new();
+    let mut removed = String::new();
+    let mut added = String::new();
+    for (op, text) in ops {
+        match op {
+            Op::Removed => removed.push_str(&text),
+            Op::Added => added.push_str(&text),
+            Op::Same => {
+                flush(&mut out, &mut removed, &mut added);
+                push(&mut out, Op::Same, &text);
+            }
+        }
+    }
+    flush(&mut out, &mut removed, &mut added);
+    out
+}
+
+fn flush(out: &mut Vec<(Op, String)>, removed: &mut String, added: &mut String) {
+    let prefix: usize = removed
+        .chars()
+        .zip(added.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    let suffix: usize = removed[prefix..]
+        .chars()
+        .rev()
+        .zip(added[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    push(out, Op::Same, &removed[..prefix]);
+    push(out, Op::Removed, &removed[prefix..removed.len() - suffix]);
+    push(out, Op::Added, &added[prefix..added.len() - suffix]);
+    push(out, Op::Same, &removed[removed.len() - suffix..]);
+    removed.clear();
+    added.clear();
+}
@@ -0,0 +1,111 @@
+use crate::error::Error;
+use crate::i18n::msg;
+use crate::source::TERMINAL;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 把名称逐行写入临时文件并用编辑器打开，返回编辑后的名称
+///
+/// 编辑器依次取自 `$VISUAL`、`$EDITOR`，都未设置时使用平台默认编辑器。
+/// 编辑后的行数必须与原始行数一致，第 N 行即第 N 个文件的新名称。
+pub fn edit_names(names: &[String]) -> Result<Vec<String>, Error> {
+    if let Some(name) = names.iter().find(|n| n.contains(['\n', '\r'])) {
+        return Err(Error::Usage(msg::name_has_newline(format!("{:?}", name))));
+    }
+
+    // 放在只有当前用户可访问的目录中，其他用户无法预先放置符号链接，也无法在编辑期间改写计划
+    let dir = private_dir()?;
+    let tmp = dir.join("names.txt");
+    let mut content = names.join("\n");
+    content.push('\n');
+    let result = write_new(&tmp, &content)
+        .map_err(Error::from)
+        .and_then(|()| run_editor(&tmp))
+        .and_then(|()| Ok(fs::read_to_string(&tmp)?));
+    let _ = fs::remove_dir_all(&dir);
+    let edited = result?;
+
+    let mut lines: Vec<String> = edited
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect();
+    // 忽略编辑器在末尾追加的空行
+    while lines.last().is_some_and(|line| line.is_empty()) && lines.len() > names.len() {
+        lines.pop();
+    }
+    if lines.len() != names.len() {
+        return Err(Error::Usage(msg::line_count_mismatch(lines.len(), names.len())));
+    }
+    Ok(lines)
+}
+
+/// 在系统临时目录中新建权限为 0700 的目录，名称已存在（包括符号链接）时换一个名称重试
+fn private_dir() -> io::Result<PathBuf> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    let mut attempt = 0u32;
+    loop {
+        let dir = env::temp_dir().join(format!(
+            "rename-cli-{}-{:08x}",
+            std::process::id(),
+            nanos.wrapping_add(attempt.wrapping_mul(0x9e37_79b9))
+        ));
+        match builder.create(&dir) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+            result => return result.map(|()| dir),
+        }
+    }
+}
+
+/// 创建新文件并写入内容，文件已存在时失败；Unix 上权限为 0600
+fn write_new(path: &Path, content: &str) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content.as_bytes())
+}
+
+fn run_editor(file: &Path) -> Result<(), Error> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| default_editor().to_string());
+    // 允许 `code --wait` 这类带参数的编辑器命令
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| Error::Usage(msg::editor_empty().to_string()))?;
+    let mut command = Command::new(program);
+    command.args(parts).arg(file);
+    // 标准输入被文件列表占用时，让编辑器直接使用终端
+    if !io::stdin().is_terminal()
+        && let Ok(terminal) = fs::File::open(TERMINAL)
+    {
+        command.stdin(terminal);
+    }
+    let status = command
+        .status()
+        .map_err(|e| Error::Usage(msg::editor_failed(&editor, e)))?;
+    if !status.success() {
+        return Err(Error::Cancelled);
+    }
+    Ok(())
+}
+
+fn default_editor() -> &'static str {
+    if cfg!(windows) { "notepad" } else { "vi" }
+}
@@ -0,0 +1,73 @@
+use crate::i18n::msg;
+use std::fmt;
+use std::io;
+
+/// 运行错误，不同类别对应不同的进程退出码，便于脚本判断
+#[derive(Debug)]
+pub enum Error {
+    /// 参数或输入无效：目录不存在、Glob/正则语法错误等
+    Usage(String),
+    /// 没有任何文件匹配
+    NothingMatched(String),
+    /// 用户取消了操作
+    Cancelled,
+    /// 部分重命名失败，另一部分已经生效
+    PartialFailure(String),
+    /// 没有任何重命名生效的失败，包括冲突中止和已回滚的批次
+    Failure(String),
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Failure(_) => 1,
+            Error::Usage(_) => 2,
+            Error::NothingMatched(_) => 3,
+            Error::Cancelled => 4,
+            Error::PartialFailure(_) => 5,
+        }
+    }
+
+    /// 机器可读的稳定标识，用于 JSON 输出
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Failure(_) => "failure",
+            Error::Usage(_) => "usage",
+            Error::NothingMatched(_) => "nothing_matched",
+            Error::Cancelled => "cancelled",
+            Error::PartialFailure(_) => "partial_failure",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg)
+            | Error::NothingMatched(msg)
+            | Error::PartialFailure(msg)
+            | Error::Failure(msg) => f.write_str(msg),
+            Error::Cancelled => f.write_str(msg::cancelled()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Failure(e.to_string())
+    }
+}
+
+impl From<glob::PatternError> for Error {
+    fn from(e: glob::PatternError) -> Self {
+        Error::Usage(msg::invalid_glob(e))
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(e: regex::Error) -> Self {
+        Error::Usage(msg::invalid_regex(e))
+    }
+}
@@ -0,0 +1,73 @@
+use crate::i18n::msg;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// 重命名计划的导出格式
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `[{"old": .., "new": ..}]`，可直接作为 --mapping 的输入
+    #[value(help = msg::value_export_json())]
+    Json,
+    /// 带 `old,new` 表头的 CSV，可直接作为 --mapping 的输入
+    #[value(help = msg::value_export_csv())]
+    Csv,
+    /// 由 `mv -n` 命令组成的 POSIX shell 脚本，按依赖顺序排列
+    #[value(help = msg::value_export_sh())]
+    Sh,
+}
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    old: &'a str,
+    new: &'a str,
+}
+
+/// 导出重命名计划
+///
+/// JSON/CSV 导出逻辑上的 旧路径 -> 新路径；脚本导出可依次执行的步骤，
+/// 包含打破环形重命名所需的临时名称。
+pub fn write(
+    out: &mut dyn Write,
+    format: ExportFormat,
+    root: &Path,
+    renames: &[(String, String)],
+    steps: &[(String, String)],
+) -> io::Result<()> {
+    match format {
+        ExportFormat::Json => {
+            let entries: Vec<Entry> = renames
+                .iter()
+                .map(|(old, new)| Entry { old, new })
+                .collect();
+            serde_json::to_writer_pretty(&mut *out, &entries)?;
+            writeln!(out)
+        }
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(["old", "new"])?;
+            for (old, new) in renames {
+                writer.write_record([old, new])?;
+            }
+            writer.flush()
+        }
+        ExportFormat::Sh => {
+            writeln!(out, "#!/bin/sh")?;
+            // 导出的脚本与界面语言无关
+            writeln!(out, "# Generated by rename-cli, {} renames", renames.len())?;
+            writeln!(out, "set -e")?;
+            let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+            writeln!(out, "cd -- {}", shell_quote(&root.to_string_lossy()))?;
+            for (old, new) in steps {
+                writeln!(out, "mv -n -- {} {}", shell_quote(old), shell_quote(new))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 用单引号包裹，内部的单引号写作 `'\''`
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
@@ -0,0 +1,135 @@
+use crate::i18n::msg;
+use clap::ValueEnum;
+
+/// 由多个部分组成、应作为整体看待的扩展名
+const COMPOUND_EXTS: [&str; 7] = [
+    "tar.gz", "tar.bz2", "tar.xz", "tar.zst", "tar.lz", "tar.lzma", "tar.z",
+];
+
+/// 同一格式的常见别名，`--normalize-ext` 时统一为右侧的写法
+const EXT_ALIASES: [(&str, &str); 6] = [
+    ("jpeg", "jpg"),
+    ("jpe", "jpg"),
+    ("tiff", "tif"),
+    ("htm", "html"),
+    ("yml", "yaml"),
+    ("mpeg", "mpg"),
+];
+
+/// 转换作用的范围
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scope {
+    /// 整个文件名
+    #[default]
+    #[value(help = msg::value_scope_name())]
+    Name,
+    /// 仅主名（不含扩展名）
+    #[value(help = msg::value_scope_stem())]
+    Stem,
+    /// 仅扩展名
+    #[value(help = msg::value_scope_ext())]
+    Ext,
+}
+
+/// 拆分主名与扩展名（不含点）
+///
+/// `.tar.gz` 等复合扩展名作为整体返回；以点开头的隐藏文件没有扩展名。
+pub fn split_ext(file_name: &str) -> (&str, &str) {
+    let lower = file_name.to_lowercase();
+    for compound in COMPOUND_EXTS {
+        // 仅在大小写转换不改变长度时按字节位置切分
+        if lower.len() == file_name.len()
+            && lower.ends_with(&format!(".{}", compound))
+            && file_name.len() > compound.len() + 1
+        {
+            let i = file_name.len() - compound.len() - 1;
+            return (&file_name[..i], &file_name[i + 1..]);
+        }
+    }
+    match file_name.rfind('.') {
+        Some(i) if i > 0 => (&file_name[..i], &file_name[i + 1..]),
+        _ => (file_name, ""),
+    }
+}
+
+/// 将 `f` 作用于文件名中 `scope` 指定的部分，其余部分保持不变
+pub fn map_scope(name: &str, scope: Scope, f: impl FnOnce(&str) -> String) -> String {
+    let (stem, ext) = split_ext(name);
+    match scope {
+        Scope::Name => f(name),
+        Scope::Stem => join(&f(stem), ext, name),
+        Scope::Ext => join(stem, &f(ext), name),
+    }
+}
+
+/// 分别转换主名与扩展名后重新拼接，扩展名分隔符保持不变
+pub fn map_parts(
+    name: &str,
+    stem: impl FnOnce(&str) -> String,
+    ext: impl FnOnce(&str) -> String,
+) -> String {
+    let (s, e) = split_ext(name);
+    join(&stem(s), &ext(e), name)
+}
+
+/// 重新拼接主名与扩展名；原名以点结尾时保留这个点
+fn join(stem: &str, ext: &str, original: &str) -> String {
+    if ext.is_empty() && !original.ends_with('.') {
+        stem.to_string()
+    } else {
+        format!("{}.{}", stem, ext)
+    }
+}
+
+/// 单个扩展名操作，与其他操作一起按命令行中出现的顺序执行
+#[derive(Debug, Clone)]
+pub enum ExtOp {
+    /// 转为小写并统一别名
+    Normalize,
+    /// 删除扩展名
+    Remove,
+    /// 设置扩展名，没有扩展名时追加
+    Set(String),
+    /// 在文件名末尾追加扩展名
+    Add(String),
+}
+
+impl ExtOp {
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            ExtOp::Normalize => map_scope(name, Scope::Ext, normalize),
+            ExtOp::Remove => split_ext(name).0.to_string(),
+            ExtOp::Set(ext) => with_ext(split_ext(name).0, ext),
+            ExtOp::Add(ext) => with_ext(name, ext),
+        }
+    }
+
+    /// 预览前显示的说明，如 `设置为 .md`
+    pub fn describe(&self) -> String {
+        match self {
+            ExtOp::Normalize => msg::ext_normalize().to_string(),
+            ExtOp::Remove => msg::ext_remove().to_string(),
+            ExtOp::Set(ext) => msg::ext_set(ext.trim_start_matches('.')),
+            ExtOp::Add(ext) => msg::ext_add(ext.trim_start_matches('.')),
+        }
+    }
+}
+
+/// 接受 `md` 或 `.md` 两种写法，空扩展名表示不追加
+fn with_ext(stem: &str, ext: &str) -> String {
+    let ext = ext.trim_start_matches('.');
+    if ext.is_empty() {
+        stem.to_string()
+    } else {
+        format!("{}.{}", stem, ext)
+    }
+}
+
+/// 扩展名转为小写并统一常见别名：`JPEG`、`jpeg`、`JPG` -> `jpg`
+fn normalize(ext: &str) -> String {
+    let lower = ext.to_lowercase();
+    EXT_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == lower)
+        .map_or(lower, |(_, canonical)| canonical.to_string())
+}
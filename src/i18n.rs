@@ -0,0 +1,308 @@
+use clap::ValueEnum;
+use crate::config;
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 界面语言
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    /// English
+    En,
+    /// 简体中文
+    Zh,
+}
+
+static LANG: AtomicU8 = AtomicU8::new(Lang::En as u8);
+
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn lang() -> Lang {
+    if LANG.load(Ordering::Relaxed) == Lang::Zh as u8 {
+        Lang::Zh
+    } else {
+        Lang::En
+    }
+}
+
+/// 确定界面语言：`--lang` 优先，其次依次为 `LC_ALL`、`LC_MESSAGES`、`LANG`，默认英文
+///
+/// 需要在解析参数之前调用，帮助信息才能使用对应的语言，因此直接扫描原始参数。
+pub fn detect() -> Lang {
+    let argv: Vec<_> = env::args_os().collect();
+    if let Some(lang) = config::scan_long_option(&argv, "--lang")
+        .iter()
+        .find_map(|value| Lang::from_str(value, true).ok())
+    {
+        return lang;
+    }
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map_or(Lang::En, |locale| from_locale(&locale))
+}
+
+/// `zh_CN.UTF-8`、`zh_SG`、`zh` 等取中文，其余（包括 `C`、`POSIX`）取英文
+fn from_locale(locale: &str) -> Lang {
+    if locale.to_lowercase().starts_with("zh") {
+        Lang::Zh
+    } else {
+        Lang::En
+    }
+}
+
+/// 定义不带参数的文本，按当前语言返回对应的静态字符串
+macro_rules! texts {
+    ($($(#[$doc:meta])* $name:ident => $en:literal, $zh:literal;)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name() -> &'static str {
+                match $crate::i18n::lang() {
+                    $crate::i18n::Lang::En => $en,
+                    $crate::i18n::Lang::Zh => $zh,
+                }
+            }
+        )*
+    };
+}
+
+/// 定义带参数的消息，格式字符串中按名称引用参数，两种语言必须使用相同的参数
+macro_rules! messages {
+    ($($(#[$doc:meta])* $name:ident($($arg:ident),*) => $en:literal, $zh:literal;)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name($($arg: impl std::fmt::Display),*) -> String {
+                match $crate::i18n::lang() {
+                    $crate::i18n::Lang::En => format!($en),
+                    $crate::i18n::Lang::Zh => format!($zh),
+                }
+            }
+        )*
+    };
+}
+
+/// 界面文本目录，每条消息同时给出英文和简体中文
+///
+/// 只翻译面向人阅读的文本；JSON 记录的字段名、导出格式和退出码与语言无关。
+pub mod msg {
+    texts! {
+        // --- 命令行帮助 ---
+        after_help => "Exit codes: 0 success, 1 failure with nothing changed, 2 invalid arguments, 3 no matching files, 4 cancelled by user, 5 some renames failed",
+            "退出码: 0 成功，1 失败且未做任何修改，2 参数错误，3 没有匹配的文件，4 用户取消，5 部分重命名失败";
+        help_path => "Directory to work in", "要处理的目录";
+        help_pattern => "Glob pattern that selects the files to rename", "筛选要重命名的文件的 Glob 模式";
+        help_from_str => "Text to replace", "要被替换的字符串";
+        help_to_str => "Replacement text; may contain sequence-number placeholders (see --start)", "替换为的字符串，可包含序号占位符（参见 --start）";
+        help_yes => "Skip the final confirmation and rename right away", "跳过最终确认，直接执行重命名";
+        help_regex => "Treat <FROM_STR> and -r FROM as regular expressions; $1, ${name} in the replacement refer to capture groups",
+            "将 <FROM_STR> 和 -r 的 FROM 视为正则表达式，替换字符串中可用 $1、${name} 引用捕获组";
+        help_first => "Replace only the first match (all matches by default)", "只替换第一处匹配（默认替换全部）";
+        help_on_conflict => "What to do when target names conflict", "目标名称冲突时的处理方式";
+        help_all => "Show every file in listings and previews (at most 50 by default)", "在列表和预览中显示全部文件（默认最多显示 50 个）";
+        help_recursive => "Descend into subdirectories; patterns containing '/' match the relative path (e.g. **/*.log), others match the file name",
+            "递归处理子目录；模式含 '/' 时匹配相对路径（如 **/*.log），否则匹配文件名";
+        help_max_depth => "Maximum recursion depth; 1 means only entries directly under <PATH>", "递归的最大深度，1 表示只处理 <PATH> 下的直接文件";
+        help_types => "Entry types to process, combinable, e.g. f,d,l", "要处理的条目类型，可组合，如 f,d,l";
+        help_keep_going => "Keep going when a rename fails instead of rolling back the whole batch", "某个重命名失败时继续执行其余重命名，而不是回滚整个批次";
+        help_edit => "Edit the matched file names line by line in $EDITOR (<PATTERN> defaults to *)", "在 $EDITOR 中逐行编辑匹配到的文件名（<PATTERN> 缺省为 *）";
+        help_start => "First value of the sequence number {n}, {n:03} in <TO_STR>", "<TO_STR> 中序号 {n}、{n:03} 的起始值";
+        help_step => "Increment of the sequence number", "序号的步长";
+        help_reset_per_dir => "Restart numbering in every directory", "每个目录分别从起始值开始编号";
+        help_sort => "Order of files in the preview and for numbering", "预览和编号时文件的排列顺序";
+        help_template => "Build new names from a template such as '{mtime:%Y-%m-%d}_{stem|slug}.{ext}'; <FROM_STR> then becomes an optional regex providing captures {1}, {name}",
+            "用模板生成新名称，如 '{mtime:%Y-%m-%d}_{stem|slug}.{ext}'；此时 <FROM_STR> 可选，作为提供捕获组 {1}、{name} 的正则";
+        help_case => "Convert case; like -r, --lower, --upper and the extension options it runs in command-line order after <FROM_STR>, which may then be omitted",
+            "转换大小写；与 -r、--lower、--upper 及扩展名选项一样，在 <FROM_STR> 的替换之后按命令行中的顺序执行，指定时 <FROM_STR>/<TO_STR> 可省略";
+        help_lower => "Convert to lowercase, same as --case lower", "转为小写，同 --case lower";
+        help_upper => "Convert to uppercase, same as --case upper", "转为大写，同 --case upper";
+        help_replace => "Replace FROM with TO (split at the first '='); repeatable, each rule is a step run in command-line order. --regex, --first and --scope apply to every rule",
+            "将 FROM 替换为 TO（以第一个 '=' 分隔）；可重复指定，每条规则作为一个步骤按命令行中的顺序执行。--regex、--first、--scope 对每条规则生效";
+        help_show_steps => "Show the intermediate name after each step in the preview", "在预览中显示每个步骤执行后的中间名称";
+        help_case_scope => "Apply the case conversion to the whole name, the stem or the extension", "大小写转换作用于整个文件名、主名或扩展名";
+        help_scope => "Apply the <FROM_STR> and -r replacements to the whole name, the stem or the extension", "<FROM_STR> 和 -r 的替换只作用于整个文件名、主名或扩展名";
+        help_set_ext => "Set the extension, appending one if there is none, e.g. --set-ext md", "设置扩展名，没有扩展名时追加，如 --set-ext md";
+        help_add_ext => "Append an extension to the file name, e.g. --add-ext bak", "在文件名末尾追加扩展名，如 --add-ext bak";
+        help_remove_ext => "Remove the extension (compound extensions such as .tar.gz are removed as a whole)", "删除扩展名（.tar.gz 等复合扩展名整体删除）";
+        help_normalize_ext => "Normalize extensions: lowercase and unify aliases, e.g. JPEG/jpeg/JPG -> jpg", "规范化扩展名：转为小写并统一别名，如 JPEG/jpeg/JPG -> jpg";
+        help_from_file => "Read the paths to process from a file (- for stdin); relative paths are based on <PATH> and each file is renamed within its own directory",
+            "从文件读取待处理的路径（- 表示标准输入），相对路径基于 <PATH>，每个文件在其所在目录中重命名";
+        help_null => "Paths in the list are NUL-separated (find -print0, fd -0, git ls-files -z)", "路径列表以 NUL 分隔（find -print0、fd -0、git ls-files -z）";
+        help_mapping => "Rename according to a mapping file (old,new pairs in CSV/TSV/JSON); paths are relative to <PATH>",
+            "按映射文件（CSV/TSV/JSON 的 old,new 对）重命名，路径相对于 <PATH>";
+        help_mapping_format => "Mapping file format, detected from the extension by default", "映射文件格式，默认按扩展名推断";
+        help_export => "Export the rename plan instead of executing it: json, csv or an mv -n script", "导出重命名计划而不执行：json、csv 或 mv -n 脚本";
+        help_export_to => "Export to a file instead of standard output", "导出到文件，默认输出到标准输出";
+        help_output => "Output format: human for coloured text, json for one record per line (NDJSON)", "输出格式：human 为彩色文本，json 为每行一个记录的 NDJSON";
+        help_interactive => "Ask before each rename: y(es), n(o), a(ll), q(uit), e(dit the new name)",
+            "逐个询问每个重命名：y 执行、n 跳过、a 全部执行、q 结束、e 编辑新名称";
+        help_tui => "Build and review the renames in a full-screen terminal UI", "在全屏终端界面中编辑并检查重命名";
+        help_lang => "Interface language; defaults to LC_ALL, LC_MESSAGES or LANG", "界面语言，默认取自 LC_ALL、LC_MESSAGES 或 LANG";
+        help_preset => "Apply a named preset from .rename-cli.toml or the user config file; options given on the command line override it (repeatable)",
+            "应用 .rename-cli.toml 或用户配置文件中的命名预设，命令行中的选项优先（可重复指定）";
+        about_presets => "List the presets defined in the configuration files", "列出配置文件中定义的预设";
+        about_history => "List the batch renames that have been executed", "列出已执行的批量重命名记录";
+        about_undo => "Undo the most recent (or the given) batch rename", "撤销最近一次（或指定 id 的）批量重命名";
+        help_history_limit => "Maximum number of entries to show", "最多显示的记录条数";
+        help_undo_id => "Id of the batch to undo; defaults to the most recent batch not yet undone", "要撤销的批次 id，默认为最近一次未撤销的批次";
+        help_undo_yes => "Skip the final confirmation and undo right away", "跳过最终确认，直接执行撤销";
+
+        // --- 可选值说明 ---
+        value_abort => "Report every conflict and abort without renaming anything", "报告全部冲突并中止，不执行任何重命名";
+        value_skip => "Skip the conflicting entries", "跳过存在冲突的条目";
+        value_suffix => "Append a numeric suffix to conflicting new names, e.g. `a_1.txt`", "为冲突的新名称追加数字后缀，如 `a_1.txt`";
+        value_overwrite => "Overwrite target files that already exist on disk (duplicate targets within the plan still abort)", "覆盖磁盘上已存在的目标文件（计划内的重复目标仍会中止）";
+        value_file => "Regular file", "普通文件";
+        value_dir => "Directory", "目录";
+        value_symlink => "Symbolic link", "符号链接";
+        value_sort_name => "By path, lexicographically", "按路径的字典序";
+        value_sort_natural => "By path, comparing digit runs as numbers (file2 < file10)", "按路径的自然顺序，数字按数值比较（file2 < file10）";
+        value_sort_mtime => "By modification time, oldest first", "按修改时间，从旧到新";
+        value_sort_size => "By size, smallest first", "按文件大小，从小到大";
+        value_case_lower => "All lowercase: readme.md", "全部小写：readme.md";
+        value_case_upper => "All uppercase: README.MD", "全部大写：README.MD";
+        value_case_title => "Capitalize each word, keeping separators: My File Name", "每个单词首字母大写，保留原有分隔符：My File Name";
+        value_case_camel => "Lower camel case: myFileName", "小驼峰：myFileName";
+        value_case_snake => "Underscores: my_file_name", "下划线：my_file_name";
+        value_case_kebab => "Hyphens: my-file-name", "连字符：my-file-name";
+        value_scope_name => "The whole file name", "整个文件名";
+        value_scope_stem => "Only the stem (without the extension)", "仅主名（不含扩展名）";
+        value_scope_ext => "Only the extension", "仅扩展名";
+        value_mapping_csv => "Comma-separated, one `old,new` per line", "逗号分隔，每行 `old,new`";
+        value_mapping_tsv => "Tab-separated, one `old<TAB>new` per line", "制表符分隔，每行 `old<TAB>new`";
+        value_mapping_json => "`[[\"old\", \"new\"]]`, `[{\"old\": .., \"new\": ..}]` or `{\"old\": \"new\"}`",
+            "`[[\"old\", \"new\"]]`、`[{\"old\": .., \"new\": ..}]` 或 `{\"old\": \"new\"}`";
+        value_export_json => "`[{\"old\": .., \"new\": ..}]`, usable as --mapping input", "`[{\"old\": .., \"new\": ..}]`，可直接作为 --mapping 的输入";
+        value_export_csv => "CSV with an `old,new` header, usable as --mapping input", "带 `old,new` 表头的 CSV，可直接作为 --mapping 的输入";
+        value_export_sh => "POSIX shell script of `mv -n` commands in dependency order", "由 `mv -n` 命令组成的 POSIX shell 脚本，按依赖顺序排列";
+        value_output_human => "Coloured text for people", "面向人阅读的彩色文本";
+        value_output_json => "One JSON record per line (NDJSON) with stable field names", "每行一个 JSON 记录（NDJSON），字段名保持稳定";
+
+        // --- 运行过程 ---
+        error_label => "Error:", "错误:";
+        warning_label => "Warning:", "警告:";
+        success_label => "Success:", "成功:";
+        preview_heading => "Rename preview:", "重命名预览:";
+        match_preview_heading => "Matched files and rename preview:", "匹配到的文件及重命名预览:";
+        prompt_pattern => "Filter pattern (glob): ", "筛选模式(Glob): ";
+        no_pattern => "No filter pattern entered.", "未输入筛选模式。";
+        replace_heading => "Replace <A> with <B>:\n", "将 <A> 替换为 <B>:\n";
+        replace_heading_regex => "Replace <A> (regex) with <B>:\n", "将 <A>(Regex) 替换为 <B>:\n";
+        empty_from => "The string <A> to replace must not be empty.", "要被替换的字符串 <A> 不能为空。";
+        rule_missing_equals => "expected FROM=TO", "格式应为 FROM=TO";
+        empty_rule_from => "FROM must not be empty", "FROM 不能为空";
+        json_to_stdout => "--output json conflicts with exporting to standard output; use --export-to to name a file.",
+            "--output json 与导出到标准输出冲突，请使用 --export-to 指定文件。";
+        nothing_to_rename => "Nothing to rename.", "没有需要重命名的文件。";
+        ext_normalize => "normalize", "规范化";
+        ext_remove => "remove", "删除";
+        tag_case_only => "[case only]", "[仅大小写]";
+        suffix_heading => "\nConflicting files will get a suffix:", "\n冲突文件将追加后缀:";
+        confirm_prompt => "\nProceed? (y/N): ", "\n是否继续? (y/N): ";
+        confirm_skip_prompt => "\nProceed? (y/N, or skip 3-7,12 to exclude entries): ", "\n是否继续? (y/N，或 skip 3-7,12 排除部分条目): ";
+        exclude_prompt => "\nExclude entries and check again? (skip 3-7,12, or Enter to give up): ", "\n排除部分条目后重新检查? (skip 3-7,12，直接回车放弃): ";
+        empty_selection => "no entry numbers given", "没有给出条目编号";
+        per_file_help => "y - rename this file\nn - skip this file\na - rename this file and all remaining ones\nq - skip this file and all remaining ones\ne - edit the new name",
+            "y - 重命名此文件\nn - 跳过此文件\na - 重命名此文件及其余全部文件\nq - 跳过此文件及其余全部文件\ne - 编辑新名称";
+        renaming => "\nRenaming...", "\n开始执行重命名...";
+        rolling_back => "Rolling back the renames completed in this batch...", "正在回滚本批次已完成的重命名...";
+        rolled_back => "Renaming failed; the whole batch has been rolled back.", "重命名失败，本批次已全部回滚。";
+        no_history => "No renames recorded yet.", "暂无重命名记录。";
+        undone_tag => "undone", "已撤销";
+        nothing_to_undo => "No batch to undo.", "没有可撤销的批次。";
+        cancelled => "Operation cancelled.", "操作已取消。";
+        editor_empty => "The editor command is empty.", "编辑器命令为空。";
+        no_data_dir => "Cannot determine the data directory", "无法确定数据目录";
+
+        // --- 全屏界面 ---
+        tui_pattern => "Pattern", "模式";
+        tui_from => "Replace", "替换";
+        tui_from_regex => "Replace (regex)", "替换(Regex)";
+        tui_to => "With", "替换为";
+        tui_help => "Tab next field · ↑↓ PgUp PgDn move · Space toggle row · a toggle all · Enter confirm · Esc cancel",
+            "Tab 切换字段 · ↑↓ PgUp PgDn 移动 · 空格 选择/排除 · a 全选/全不选 · Enter 确认 · Esc 取消";
+        tui_needs_terminal => "--tui needs an interactive terminal.", "--tui 需要在交互式终端中运行。";
+        tui_nothing_selected => "No rename selected.", "没有选中任何重命名。";
+
+        // --- 冲突类型 ---
+        conflict_duplicate => "same name as another file in the plan", "与计划中的其他文件重名";
+        conflict_exists => "target exists", "目标已存在";
+        conflict_invalid => "invalid file name", "无效的文件名";
+        conflict_missing => "source does not exist", "源文件不存在";
+    }
+
+    messages! {
+        // --- 运行过程 ---
+        not_a_dir(path) => "'{path}' is not a valid directory.", "'{path}' 不是一个有效的目录。";
+        mapping_heading(path) => "Mapping {path}:", "映射 {path}:";
+        list_heading(path) => "List {path}:", "列出 {path}:";
+        empty_list(path) => "File list '{path}' is empty.", "文件列表 '{path}' 为空。";
+        empty_dir(path) => "Directory '{path}' is empty or contains no files.", "目录 '{path}' 为空或不包含文件。";
+        pattern_line(pattern) => "Pattern: {pattern}", "模式: {pattern}";
+        capture_line(regex) => "Capture (regex): '{regex}'", "捕获(Regex): '{regex}'";
+        template_line(template) => "Template: '{template}'", "模板: '{template}'";
+        replace_line(from, to) => "Replace: '{from}' -> '{to}'", "替换: '{from}' -> '{to}'";
+        replace_regex_line(from, to) => "Replace (regex): '{from}' -> '{to}'", "替换(Regex): '{from}' -> '{to}'";
+        case_line(style, scope) => "Case: {style} ({scope})", "大小写: {style} ({scope})";
+        ext_line(ops) => "Extension: {ops}", "扩展名: {ops}";
+        ext_set(ext) => "set to .{ext}", "设置为 .{ext}";
+        ext_add(ext) => "append .{ext}", "追加 .{ext}";
+        step_trace(step, name) => "step {step}: {name}", "第 {step} 步: {name}";
+        no_match(pattern) => "\nNo files match pattern '{pattern}'", "\n没有文件匹配模式 '{pattern}'";
+        exported(count, file) => "\nExported {count} renames to {file}.", "\n已将 {count} 个重命名导出到 {file}。";
+        tag_conflict(kind) => "[conflict: {kind}]", "[冲突: {kind}]";
+        conflicts_found(count) => "Conflicts found: {count}; nothing was renamed. Use --on-conflict=skip|suffix|overwrite to resolve them.",
+            "检测到 {count} 处冲突，未执行任何重命名。可使用 --on-conflict=skip|suffix|overwrite 处理冲突。";
+        skipped_conflicts(count) => "\nSkipped {count} conflicting files.", "\n已跳过 {count} 个存在冲突的文件。";
+        will_overwrite(label, count) => "\n{label} {count} existing target files will be overwritten.", "\n{label} {count} 个已存在的目标文件将被覆盖。";
+        truncated(shown, total) => "... showing {shown} of {total}; use --all to show everything", "... 仅显示 {shown} / {total} 个，使用 --all 显示全部";
+        renamed(old, new) => "Renamed: {old} -> {new}", "已重命名: {old} -> {new}";
+        rename_failed(path, error) => "Failed to rename {path}: {error}", "重命名失败 {path}: {error}";
+        restored(old, new) => "Restored: {old} -> {new}", "已恢复: {old} -> {new}";
+        restore_failed(path, error) => "Failed to restore {path}: {error}", "恢复失败 {path}: {error}";
+        rename_done(label) => "\n{label} Renaming complete.", "\n{label} 重命名完成。";
+        partial_failure(failed, applied) => "{failed} renames failed, {applied} steps remain in effect.", "{failed} 个重命名失败，{applied} 个步骤仍然生效。";
+        batch_recorded(id) => "Recorded as batch #{id}; run `rename-cli undo {id}` to undo it.", "已记录为批次 #{id}，可使用 `rename-cli undo {id}` 撤销。";
+        journal_failed(label, error) => "{label} Cannot write the undo journal: {error}", "{label} 无法写入撤销日志: {error}";
+        history_line(id, time, dir, count, status) => "{id} {time} {dir} ({count} files) {status}", "{id} {time} {dir} ({count} 个文件) {status}";
+        batch_not_found(id) => "Batch #{id} not found.", "找不到批次 #{id}。";
+        batch_undone(id) => "Batch #{id} has already been undone.", "批次 #{id} 已被撤销。";
+        undo_heading(id, time, dir) => "Undo batch #{id} ({time}) {dir}:", "撤销批次 #{id} ({time}) {dir}:";
+        undo_done(label, id) => "\n{label} Batch #{id} undone.", "\n{label} 已撤销批次 #{id}。";
+        invalid_selection(spec, reason) => "Invalid selection '{spec}': {reason}", "无效的选择 '{spec}': {reason}";
+        index_out_of_range(value, max) => "'{value}' is not an entry number between 1 and {max}", "'{value}' 不是 1 到 {max} 之间的条目编号";
+        reversed_range(range) => "range '{range}' ends before it starts", "范围 '{range}' 的结束小于开始";
+        edit_name_prompt(name) => "New name [{name}]: ", "新名称 [{name}]: ";
+        tui_list_title(selected, changed, matched, total) => " {selected} / {changed} renames selected · {matched} / {total} entries match ",
+            " 已选 {selected} / {changed} 个重命名 · 匹配 {matched} / {total} 个条目 ";
+        tui_conflicts(count) => "{count} conflicts among the selected renames", "选中的重命名中有 {count} 处冲突";
+        no_presets(paths) => "No presets defined. Configuration files: {paths}", "尚未定义预设。配置文件: {paths}";
+        non_utf8_path(path) => "Skipping non-UTF-8 path: {path}", "跳过非 UTF-8 路径: {path}";
+
+        // --- 参数和输入错误 ---
+        invalid_glob(error) => "Invalid glob pattern: {error}", "无效的 Glob 模式: {error}";
+        invalid_regex(error) => "Invalid regular expression: {error}", "无效的正则表达式: {error}";
+        name_has_newline(name) => "File name {name} contains a line break and cannot be edited in an editor.", "文件名 {name} 包含换行符，无法在编辑器中编辑。";
+        line_count_mismatch(lines, files) => "The edited file has {lines} lines but there are {files} files; do not add or remove lines.",
+            "编辑后的行数 ({lines}) 与文件数 ({files}) 不一致，请勿增删行。";
+        editor_failed(editor, error) => "Cannot start editor '{editor}': {error}", "无法启动编辑器 '{editor}': {error}";
+        mapping_unreadable(path, error) => "Cannot read mapping file '{path}': {error}", "无法读取映射文件 '{path}': {error}";
+        mapping_invalid(path, error) => "Malformed mapping file '{path}': {error}", "映射文件 '{path}' 格式错误: {error}";
+        mapping_columns(line, count) => "line {line} should have 2 columns but has {count}", "第 {line} 行应有 2 列，实际为 {count} 列";
+        mapping_duplicate(path) => "source '{path}' appears more than once", "源文件 '{path}' 出现了多次";
+        template_unclosed(template) => "Unclosed '{{' in template '{template}'", "模板 '{template}' 中的 '{{' 未闭合";
+        template_stray_close(template) => "Unmatched '}}' in template '{template}'", "模板 '{template}' 中有多余的 '}}'";
+        template_unknown(token) => "Unknown placeholder '{{{token}}}' in template", "模板中存在未知的占位符 '{{{token}}}'";
+        invalid_date_format(format) => "Invalid date format '{format}'", "无效的日期格式 '{format}'";
+        invalid_number_spec(spec) => "Invalid number format '{spec}'", "无效的序号格式 '{spec}'";
+        config_unreadable(path, error) => "Cannot read configuration file '{path}': {error}", "无法读取配置文件 '{path}': {error}";
+        config_invalid(path, error) => "Malformed configuration file '{path}': {error}", "配置文件 '{path}' 格式错误: {error}";
+        preset_not_found(name) => "Preset '{name}' not found; run `rename-cli presets` to list them", "找不到预设 '{name}'，可使用 `rename-cli presets` 列出全部预设";
+        preset_unknown_option(name, key) => "Preset '{name}': unknown option '{key}'", "预设 '{name}': 未知的选项 '{key}'";
+        preset_reserved_option(name, key) => "Preset '{name}': '{key}' can only be given on the command line", "预设 '{name}': '{key}' 只能在命令行中给出";
+        preset_invalid_value(name, key) => "Preset '{name}': '{key}' must be a string, an integer, a boolean or an array of them",
+            "预设 '{name}': '{key}' 的值必须是字符串、整数、布尔值或由它们组成的数组";
+        unknown_filter(filter) => "Unknown filter '{filter}'", "未知的过滤器 '{filter}'";
+    }
+}
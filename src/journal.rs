@@ -0,0 +1,181 @@
+use crate::i18n::msg;
+use crate::plan::split_name;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// 一次已执行的批量重命名
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Batch {
+    pub id: u64,
+    pub timestamp: DateTime<Local>,
+    pub dir: PathBuf,
+    /// 最终生效的 旧名称 -> 新名称，不包含临时名称等中间步骤
+    pub renames: Vec<(String, String)>,
+    #[serde(default)]
+    pub undone: bool,
+}
+
+impl Batch {
+    /// 生成撤销用的重命名计划
+    ///
+    /// 日志中的路径以执行前的父目录表示（子条目先于父目录重命名），
+    /// 撤销时需换算为当前路径：`a/x -> a/y` 与 `a -> b` 的撤销为 `b/y -> b/x` 与 `b -> a`。
+    pub fn inverse(&self) -> Vec<(String, String)> {
+        let renamed: HashMap<&str, &str> = self
+            .renames
+            .iter()
+            .map(|(old, new)| (old.as_str(), new.as_str()))
+            .collect();
+        self.renames
+            .iter()
+            .map(|(old, new)| {
+                let (parent, name) = split_name(old);
+                let parent = current_path(parent, &renamed);
+                (current_path(new, &renamed), format!("{}{}", parent, name))
+            })
+            .collect()
+    }
+}
+
+/// 将以原始父目录表示的路径换算为本批次执行后的路径
+fn current_path(path: &str, renamed: &HashMap<&str, &str>) -> String {
+    let (parent, name) = split_name(path);
+    if parent.is_empty() {
+        return path.to_string();
+    }
+    // 父目录前缀不含末尾分隔符时才能在映射中查找
+    let parent_key = &parent[..parent.len() - 1];
+    let separator = &parent[parent.len() - 1..];
+    let parent = renamed.get(parent_key).copied().unwrap_or(parent_key);
+    format!("{}{}{}", current_path(parent, renamed), separator, name)
+}
+
+/// 日志文件位置：`$XDG_DATA_HOME/rename-cli/journal.jsonl`（或对应平台的数据目录）
+pub fn journal_path() -> io::Result<PathBuf> {
+    let base = dirs::data_local_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, msg::no_data_dir()))?;
+    Ok(base.join("rename-cli").join("journal.jsonl"))
+}
+
+/// 读取全部批次，按 id 升序
+pub fn load() -> io::Result<Vec<Batch>> {
+    let path = journal_path()?;
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut batches = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        batches.push(serde_json::from_str(&line)?);
+    }
+    Ok(batches)
+}
+
+/// 将执行过的步骤合并为一个批次并追加到日志，返回批次 id
+///
+/// `steps` 为实际成功执行的步骤（可能包含临时名称），会被合并为净映射。
+pub fn record(dir: &Path, steps: &[(String, String)]) -> io::Result<Option<u64>> {
+    let renames = compose(steps);
+    if renames.is_empty() {
+        return Ok(None);
+    }
+    let id = load()?.last().map_or(1, |b| b.id + 1);
+    let batch = Batch {
+        id,
+        timestamp: Local::now(),
+        dir: fs::canonicalize(dir)?,
+        renames,
+        undone: false,
+    };
+    let path = journal_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(&batch)?)?;
+    Ok(Some(id))
+}
+
+/// 将批次标记为已撤销，重写整个日志文件
+pub fn mark_undone(id: u64) -> io::Result<()> {
+    let mut batches = load()?;
+    for batch in batches.iter_mut().filter(|b| b.id == id) {
+        batch.undone = true;
+    }
+    let path = journal_path()?;
+    let tmp = path.with_extension("jsonl.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    for batch in &batches {
+        writeln!(file, "{}", serde_json::to_string(batch)?)?;
+    }
+    file.sync_all()?;
+    fs::rename(tmp, path)
+}
+
+/// 把依次执行的步骤合并为 原始名称 -> 最终名称 的映射，保持首次出现的顺序
+fn compose(steps: &[(String, String)]) -> Vec<(String, String)> {
+    // 当前名称 -> 原始名称
+    let mut origin: HashMap<String, String> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+    for (src, dst) in steps {
+        let orig = origin.remove(src).unwrap_or_else(|| {
+            order.push(src.clone());
+            src.clone()
+        });
+        origin.insert(dst.clone(), orig);
+    }
+    let mut current: HashMap<String, String> =
+        origin.into_iter().map(|(cur, orig)| (orig, cur)).collect();
+    order
+        .into_iter()
+        .filter_map(|orig| {
+            let cur = current.remove(&orig)?;
+            (cur != orig).then_some((orig, cur))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::plan;
+
+    #[test]
+    fn compose_drops_temp_names() {
+        let steps = plan(&[("a", "a.tmp"), ("b", "a"), ("a.tmp", "b")]);
+        assert_eq!(compose(&steps), plan(&[("a", "b"), ("b", "a")]));
+    }
+
+    #[test]
+    fn compose_keeps_case_only_renames() {
+        let steps = plan(&[("a.txt", ".tmp-1"), (".tmp-1", "A.txt")]);
+        assert_eq!(compose(&steps), plan(&[("a.txt", "A.txt")]));
+    }
+
+    #[test]
+    fn compose_omits_names_that_end_where_they_started() {
+        let steps = plan(&[("a", ".tmp-1"), (".tmp-1", "a"), ("b", "c")]);
+        assert_eq!(compose(&steps), plan(&[("b", "c")]));
+    }
+
+    #[test]
+    fn inverse_uses_current_parent_paths() {
+        let batch = Batch {
+            id: 1,
+            timestamp: Local::now(),
+            dir: PathBuf::from("."),
+            renames: plan(&[("a/x", "a/y"), ("a", "b")]),
+            undone: false,
+        };
+        assert_eq!(batch.inverse(), plan(&[("b/y", "b/x"), ("b", "a")]));
+    }
+}
@@ -1,175 +1,1353 @@
-use clap::Parser;
+mod case;
+mod config;
+mod diff;
+mod editor;
+mod error;
+mod export;
+mod ext;
+mod i18n;
+mod journal;
+mod mapping;
+mod pipeline;
+mod plan;
+mod select;
+mod sequence;
+mod source;
+mod template;
+mod tui;
+mod ui;
+
+use case::CaseStyle;
+use clap::parser::ValueSource;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use colored::*;
-use glob::Pattern;
+use diff::Diff;
+use error::Error;
+use export::ExportFormat;
+use ext::{ExtOp, Scope};
+use mapping::MappingFormat;
+use glob::{MatchOptions, Pattern};
+use i18n::{Lang, msg};
+use pipeline::{Pipeline, Replacer, Step};
+use plan::OnConflict;
+use regex::Regex;
+use select::Answer;
+use sequence::{Counter, SortOrder};
+use source::EntryType;
+use template::Template;
+use ui::{OutputFormat, Record, say};
+use std::collections::HashMap;
+use std::env;
 use std::fs;
-use std::io::{self, Write};
+use std::io;
 use std::path::{Path, PathBuf};
 
+/// 预览中默认最多显示的条目数，使用 `--all` 显示全部
+const PREVIEW_LIMIT: usize = 50;
+
+/// 预览中旧名称列对齐的最大宽度，更长的名称直接接上箭头
+const MAX_COLUMN: usize = 60;
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 #[command(arg_required_else_help = false)]
+#[command(args_conflicts_with_subcommands = true)]
+// 多个预设设置了同一选项时以最后一次为准；命令行中给出的选项在展开时已取代预设
+#[command(args_override_self = true)]
+#[command(group(ArgGroup::new("case_steps").args(["case", "lower", "upper"]).multiple(true)))]
+#[command(after_help = msg::after_help())]
 struct Args {
-    #[arg(default_value = ".")]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(default_value = ".", help = msg::help_path())]
     path: PathBuf,
 
-    #[arg()]
+    #[arg(help = msg::help_pattern())]
     pattern: Option<String>,
 
-    #[arg()]
+    #[arg(help = msg::help_from_str())]
     from_str: Option<String>,
 
-    #[arg()]
+    #[arg(help = msg::help_to_str())]
     to_str: Option<String>,
 
-    #[arg(short, long, help = "跳过最终确认，直接执行重命名")]
+    #[arg(short, long, help = msg::help_yes())]
     yes: bool,
+
+    #[arg(short, long, conflicts_with_all = ["yes", "tui"], help = msg::help_interactive())]
+    interactive: bool,
+
+    #[arg(
+        short = 'e',
+        long,
+        help = msg::help_regex()
+    )]
+    regex: bool,
+
+    #[arg(long, help = msg::help_first())]
+    first: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OnConflict::Abort,
+        help = msg::help_on_conflict()
+    )]
+    on_conflict: OnConflict,
+
+    #[arg(short, long, help = msg::help_all())]
+    all: bool,
+
+    #[arg(
+        short = 'R',
+        long,
+        help = msg::help_recursive()
+    )]
+    recursive: bool,
+
+    #[arg(
+        long,
+        requires = "recursive",
+        help = msg::help_max_depth()
+    )]
+    max_depth: Option<usize>,
+
+    #[arg(
+        short = 't',
+        long = "type",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "f",
+        help = msg::help_types()
+    )]
+    types: Vec<EntryType>,
+
+    #[arg(long, help = msg::help_keep_going())]
+    keep_going: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "from_str", "to_str", "regex", "first", "replace", "lower", "upper", "scope",
+            "set_ext", "add_ext", "remove_ext", "normalize_ext",
+        ],
+        help = msg::help_edit()
+    )]
+    edit: bool,
+
+    #[arg(long, default_value_t = 1, help = msg::help_start())]
+    start: u64,
+
+    #[arg(long, default_value_t = 1, help = msg::help_step())]
+    step: u64,
+
+    #[arg(long, help = msg::help_reset_per_dir())]
+    reset_per_dir: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = SortOrder::Name,
+        help = msg::help_sort()
+    )]
+    sort: SortOrder,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["to_str", "edit", "first", "regex"],
+        help = msg::help_template()
+    )]
+    template: Option<String>,
+
+    #[arg(
+        short = 'c',
+        long,
+        value_enum,
+        conflicts_with = "edit",
+        help = msg::help_case()
+    )]
+    case: Option<CaseStyle>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Scope::Name,
+        requires = "case_steps",
+        help = msg::help_case_scope()
+    )]
+    case_scope: Scope,
+
+    #[arg(long, help = msg::help_lower())]
+    lower: bool,
+
+    #[arg(long, help = msg::help_upper())]
+    upper: bool,
+
+    #[arg(
+        short = 'r',
+        long,
+        value_name = "FROM=TO",
+        value_parser = parse_rule,
+        help = msg::help_replace()
+    )]
+    replace: Vec<(String, String)>,
+
+    #[arg(long, help = msg::help_show_steps())]
+    show_steps: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Scope::Name,
+        help = msg::help_scope()
+    )]
+    scope: Scope,
+
+    #[arg(long, value_name = "EXT", help = msg::help_set_ext())]
+    set_ext: Option<String>,
+
+    #[arg(long, value_name = "EXT", help = msg::help_add_ext())]
+    add_ext: Option<String>,
+
+    #[arg(long, conflicts_with = "set_ext", help = msg::help_remove_ext())]
+    remove_ext: bool,
+
+    #[arg(long, help = msg::help_normalize_ext())]
+    normalize_ext: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "recursive",
+        help = msg::help_from_file()
+    )]
+    from_file: Option<PathBuf>,
+
+    #[arg(short = '0', long, requires = "from_file", help = msg::help_null())]
+    null: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "pattern", "from_file", "recursive", "edit", "template", "regex", "first", "scope",
+            "replace", "case", "lower", "upper", "set_ext", "add_ext", "remove_ext", "normalize_ext",
+            "start", "step", "reset_per_dir", "sort", "show_steps", "tui",
+        ],
+        help = msg::help_mapping()
+    )]
+    mapping: Option<PathBuf>,
+
+    #[arg(long, value_enum, requires = "mapping", help = msg::help_mapping_format())]
+    mapping_format: Option<MappingFormat>,
+
+    #[arg(long, value_enum, help = msg::help_export())]
+    export: Option<ExportFormat>,
+
+    #[arg(long, value_name = "FILE", requires = "export", help = msg::help_export_to())]
+    export_to: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        global = true,
+        default_value_t = OutputFormat::Human,
+        help = msg::help_output()
+    )]
+    output: OutputFormat,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["edit", "template", "mapping", "output"],
+        help = msg::help_tui()
+    )]
+    tui: bool,
+
+    #[arg(long, value_enum, global = true, help = msg::help_lang())]
+    lang: Option<Lang>,
+
+    /// 已在解析参数之前由 [`config::expand`] 展开，这里只用于帮助信息和参数校验
+    #[arg(long, value_name = "NAME", help = msg::help_preset())]
+    preset: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 列出已执行的批量重命名记录
+    #[command(about = msg::about_history())]
+    History {
+        #[arg(short = 'n', long, default_value_t = 20, help = msg::help_history_limit())]
+        limit: usize,
+    },
+    /// 撤销最近一次（或指定 id 的）批量重命名
+    #[command(about = msg::about_undo())]
+    Undo {
+        #[arg(help = msg::help_undo_id())]
+        id: Option<u64>,
+
+        #[arg(short, long, help = msg::help_undo_yes())]
+        yes: bool,
+    },
+    /// 列出配置文件中定义的预设
+    #[command(about = msg::about_presets())]
+    Presets,
+}
+
+/// 每个条目在各个步骤执行后的中间文件名及步骤编号，`--show-steps` 时显示在预览中
+type Traces = HashMap<String, Vec<(usize, String)>>;
+
+/// 只保留改变了文件名的步骤；只有一个步骤时没有中间名称可显示
+fn changed_steps(name: &str, steps: &[String]) -> Vec<(usize, String)> {
+    if steps.len() < 2 {
+        return Vec::new();
+    }
+    let previous = std::iter::once(name).chain(steps.iter().map(String::as_str));
+    steps
+        .iter()
+        .zip(previous)
+        .enumerate()
+        .filter(|(_, (step, previous))| step != previous)
+        .map(|(i, (step, _))| (i + 1, step.clone()))
+        .collect()
+}
+
+/// 解析 `-r FROM=TO`，以第一个 `=` 分隔
+fn parse_rule(rule: &str) -> Result<(String, String), String> {
+    match rule.split_once('=') {
+        Some(("", _)) => Err(msg::empty_rule_from().to_string()),
+        Some((from, to)) => Ok((from.to_string(), to.to_string())),
+        None => Err(msg::rule_missing_equals().to_string()),
+    }
+}
+
+/// 按命令行中出现的顺序收集替换、大小写和扩展名步骤
+fn collect_steps(args: &Args, matches: &ArgMatches) -> Result<Vec<Step>, Error> {
+    let index = |id: &str| {
+        (matches.value_source(id) == Some(ValueSource::CommandLine))
+            .then(|| matches.index_of(id))
+            .flatten()
+    };
+    let mut steps: Vec<(usize, Step)> = Vec::new();
+    if let Some(indices) = matches.indices_of("replace") {
+        for (i, (from, to)) in indices.zip(&args.replace) {
+            steps.push((i, Step::Replace(Replacer::new(from, to, args.regex)?)));
+        }
+    }
+    let optional = [
+        (index("case"), args.case.map(Step::Case)),
+        (index("lower"), Some(Step::Case(CaseStyle::Lower))),
+        (index("upper"), Some(Step::Case(CaseStyle::Upper))),
+        (index("normalize_ext"), Some(Step::Ext(ExtOp::Normalize))),
+        (index("remove_ext"), Some(Step::Ext(ExtOp::Remove))),
+        (index("set_ext"), args.set_ext.clone().map(|ext| Step::Ext(ExtOp::Set(ext)))),
+        (index("add_ext"), args.add_ext.clone().map(|ext| Step::Ext(ExtOp::Add(ext)))),
+    ];
+    for (i, step) in optional {
+        if let (Some(i), Some(step)) = (i, step) {
+            steps.push((i, step));
+        }
+    }
+    steps.sort_by_key(|(i, _)| *i);
+    Ok(steps.into_iter().map(|(_, step)| step).collect())
 }
 
 fn main() {
-    let args = Args::parse();
-    if let Err(e) = run(args) {
-        eprintln!("{} {}", "Error:".red(), e);
+    // 帮助信息在解析参数时生成，需要先确定语言
+    i18n::set_lang(i18n::detect());
+    // 预设同样需要在解析参数之前展开；展开失败时先按原始参数解析，以便按输出格式报告错误
+    let argv: Vec<_> = env::args_os().collect();
+    let given = given_options(&argv);
+    let (argv, preset_pattern) = match config::expand(argv.clone(), &preset_options(), &given) {
+        Ok((argv, pattern)) => (argv, Ok(pattern)),
+        Err(e) => (argv, Err(e)),
+    };
+    // 各个转换步骤按命令行中出现的顺序执行，需要保留参数的位置
+    let (matches, mut args) = parse(&argv).unwrap_or_else(|e| exit_with_usage(e, &argv));
+    ui::set_format(args.output);
+    let result = preset_pattern.and_then(|pattern| {
+        if args.pattern.is_none() {
+            args.pattern = pattern;
+        }
+        match args.command.take() {
+            Some(Command::History { limit }) => history(limit),
+            Some(Command::Undo { id, yes }) => undo(id, yes),
+            Some(Command::Presets) => presets(),
+            None => run(args, &matches),
+        }
+    });
+    match result {
+        Ok(()) => {}
+        // 取消和无匹配属于正常结束，只是需要用退出码告知调用方
+        Err(e) if ui::is_json() => {
+            ui::emit(&Record::Error {
+                kind: e.kind(),
+                message: e.to_string().trim().to_string(),
+                exit_code: e.exit_code(),
+            });
+            std::process::exit(e.exit_code());
+        }
+        Err(e @ (Error::Cancelled | Error::NothingMatched(_))) => {
+            say!("{}", e);
+            std::process::exit(e.exit_code());
+        }
+        Err(e) => {
+            eprintln!("{} {}", msg::error_label().red(), e);
+            std::process::exit(e.exit_code());
+        }
     }
 }
 
-fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+/// 解析参数，错误交给调用方按输出格式报告
+fn parse(argv: &[std::ffi::OsString]) -> Result<(ArgMatches, Args), clap::Error> {
+    let matches = Args::command().try_get_matches_from(globals_after_subcommand(argv))?;
+    let args = Args::from_arg_matches(&matches)?;
+    Ok((matches, args))
+}
+
+/// 把写在子命令之前的全局选项（`--output json history`）移到子命令之后
+///
+/// 任何选项出现后 clap 都不再识别子命令（`args_conflicts_with_subcommands`），
+/// 子命令名会被当作 <PATH>。
+fn globals_after_subcommand(argv: &[std::ffi::OsString]) -> Vec<std::ffi::OsString> {
+    let command = Args::command();
+    let globals: Vec<&str> = command
+        .get_arguments()
+        .filter(|arg| arg.is_global_set())
+        .filter_map(|arg| arg.get_long())
+        .collect();
+    // 跳过开头的全局选项及其值，找到第一个其他参数
+    let mut i = 1;
+    while let Some(long) = argv.get(i).and_then(|arg| arg.to_str()?.strip_prefix("--")) {
+        match long.split_once('=') {
+            Some((name, _)) if globals.contains(&name) => i += 1,
+            None if globals.contains(&long) => i += 2,
+            _ => break,
+        }
+    }
+    if i == 1 {
+        return argv.to_vec();
+    }
+    match argv.get(i).and_then(|arg| arg.to_str()) {
+        // `help` 的参数都是子命令名；语言在解析参数之前已经确定，全局选项直接丢弃
+        Some("help") => [&argv[..1], &argv[i..]].concat(),
+        Some(name) if command.get_subcommands().any(|sub| sub.get_name() == name) => {
+            [&argv[..1], &argv[i..=i], &argv[1..i], &argv[i + 1..]].concat()
+        }
+        _ => argv.to_vec(),
+    }
+}
+
+/// 报告参数错误并退出；指定了 `--output json` 时同样以 JSON 记录报告
+fn exit_with_usage(e: clap::Error, argv: &[std::ffi::OsString]) -> ! {
+    let json = config::scan_long_option(argv, "--output")
+        .last()
+        .and_then(|value| OutputFormat::from_str(value, true).ok())
+        == Some(OutputFormat::Json);
+    if !json || !e.use_stderr() {
+        e.exit();
+    }
+    ui::set_format(OutputFormat::Json);
+    // 只保留第一行，去掉 clap 附带的用法说明
+    let rendered = e.to_string();
+    let message = rendered.lines().next().unwrap_or_default();
+    let error = Error::Usage(message.trim_start_matches("error: ").to_string());
+    ui::emit(&Record::Error {
+        kind: error.kind(),
+        message: error.to_string(),
+        exit_code: error.exit_code(),
+    });
+    std::process::exit(error.exit_code());
+}
+
+fn run(args: Args, matches: &ArgMatches) -> Result<(), Error> {
     let path = &args.path;
     if !path.is_dir() {
-        return Err(format!("'{}' 不是一个有效的目录。", path.display()).into());
+        return Err(Error::Usage(msg::not_a_dir(path.display())));
+    }
+
+    // 导出到标准输出时，提示信息改为输出到标准错误
+    if args.export.is_some() && args.export_to.is_none() {
+        if ui::is_json() {
+            return Err(Error::Usage(msg::json_to_stdout().to_string()));
+        }
+        ui::redirect_to_stderr();
+    }
+
+    // 映射文件直接给出重命名计划，跳过列出、筛选和替换
+    if let Some(mapping) = &args.mapping {
+        say!("{}", msg::mapping_heading(mapping.display()));
+        say!("\n{}", msg::preview_heading().bold());
+        let renames = mapping::load(mapping, args.mapping_format)?;
+        return apply_plan(path, renames, &args, false, args.yes, &Traces::new());
     }
 
     // --- 1. 列出文件 ---
-    println!("List {}:", path.display());
-    let all_files = list_files_in_dir(path)?;
+    say!("{}", msg::list_heading(path.display()));
+    let max_depth = if args.recursive {
+        args.max_depth.unwrap_or(usize::MAX)
+    } else {
+        1
+    };
+    let all_files = match &args.from_file {
+        Some(list) => source::read_file_list(list, args.null, path, &args.types)?,
+        None => source::list_files_in_dir(path, max_depth, &args.types)?,
+    };
+    // 文件列表来自标准输入时，交互输入改为从终端读取
+    let tty = args.from_file.as_deref() == Some(Path::new("-"));
     if all_files.is_empty() {
-        println!("目录 '{}' 为空或不包含文件。", path.display());
-        return Ok(());
+        return Err(Error::NothingMatched(match &args.from_file {
+            Some(list) => msg::empty_list(list.display()),
+            None => msg::empty_dir(path.display()),
+        }));
     }
+    ui::emit(&Record::Listing {
+        root: &path.to_string_lossy(),
+        entries: &all_files,
+    });
+    let pipeline = Pipeline {
+        steps: collect_steps(&args, matches)?,
+        first: args.first,
+        scope: args.scope,
+        case_scope: args.case_scope,
+    };
+    if args.tui {
+        return run_tui(path, &all_files, &args, &pipeline);
+    }
+
     // 仅在交互模式下全部列出，非交互模式下会直接显示匹配结果
-    if args.pattern.is_none() {
-        for file_name in &all_files {
-            println!("{}", file_name);
+    if args.pattern.is_none() && !args.edit && args.template.is_none() {
+        let shown = preview_limit(all_files.len(), args.all);
+        for file_name in &all_files[..shown] {
+            say!("{}", file_name);
         }
+        print_truncation_note(shown, all_files.len());
     }
 
+    let has_transforms = !pipeline.is_empty();
+
     // --- 2. 获取模式和替换字符串 ---
     let pattern_str: String;
     let from_str: String;
     let to_str: String;
 
-    // 检查是进入交互模式还是非交互模式
-    if let (Some(p), Some(f), Some(t)) = (args.pattern, args.from_str, args.to_str) {
+    // 检查是进入编辑器模式、交互模式还是非交互模式
+    if args.edit {
+        pattern_str = args.pattern.clone().unwrap_or_else(|| "*".to_string());
+        from_str = String::new();
+        to_str = String::new();
+        say!("{}", "---------------------------------------------".yellow());
+        say!("{}", msg::pattern_line(pattern_str.cyan()));
+    } else if args.template.is_some() {
+        pattern_str = args.pattern.clone().unwrap_or_else(|| "*".to_string());
+        from_str = args.from_str.clone().unwrap_or_default();
+        to_str = String::new();
+        say!("{}", "---------------------------------------------".yellow());
+        say!("{}", msg::pattern_line(pattern_str.cyan()));
+        if !from_str.is_empty() {
+            say!("{}", msg::capture_line(from_str.cyan()));
+        }
+    } else if let (Some(p), true, None) = (&args.pattern, has_transforms, &args.from_str) {
+        // 只有 -r、大小写或扩展名步骤
+        pattern_str = p.clone();
+        from_str = String::new();
+        to_str = String::new();
+        say!("{}", "---------------------------------------------".yellow());
+        say!("{}", msg::pattern_line(pattern_str.cyan()));
+    } else if let (Some(p), Some(f), Some(t)) = (&args.pattern, &args.from_str, &args.to_str) {
         // 非交互模式
-        pattern_str = p;
-        from_str = f;
-        to_str = t;
-        println!("{}", "---------------------------------------------".yellow());
-        println!("模式: {}", pattern_str.cyan());
-        println!("替换: '{}' -> '{}'", from_str.cyan(), to_str.cyan());
+        pattern_str = p.clone();
+        from_str = f.clone();
+        to_str = t.clone();
+        say!("{}", "---------------------------------------------".yellow());
+        say!("{}", msg::pattern_line(pattern_str.cyan()));
     } else {
         // 交互模式
-        println!("{}", "---------------------------------------------".yellow());
-        print!("Filter pattern(Glob): ");
-        io::stdout().flush()?;
-        let mut p_input = String::new();
-        io::stdin().read_line(&mut p_input)?;
+        say!("{}", "---------------------------------------------".yellow());
+        ui::prompt(msg::prompt_pattern())?;
+        let p_input = ui::read_line(tty)?;
         pattern_str = p_input.trim().to_string();
 
         if pattern_str.is_empty() {
-            println!("未输入筛选模式，操作已取消。");
-            return Ok(());
+            say!("{}", msg::no_pattern());
+            return Err(Error::Cancelled);
         }
 
-        // 交互模式下获取替换字符串
-        println!("{}", "---------------------------------------------".yellow());
-        println!("Replace <A> to <B>:\n");
-        print!("A: ");
-        io::stdout().flush()?;
-        let mut f_input = String::new();
-        io::stdin().read_line(&mut f_input)?;
-        from_str = f_input.trim().to_string();
+        if has_transforms {
+            // 已经通过选项给出了转换步骤，只需要模式
+            from_str = String::new();
+            to_str = String::new();
+        } else {
+            // 交互模式下获取替换字符串
+            say!("{}", "---------------------------------------------".yellow());
+            if args.regex {
+                say!("{}", msg::replace_heading_regex());
+            } else {
+                say!("{}", msg::replace_heading());
+            }
+            ui::prompt("A: ")?;
+            let f_input = ui::read_line(tty)?;
+            from_str = f_input.trim().to_string();
 
-        if from_str.is_empty() {
-            println!("要被替换的字符串 <A> 不能为空。");
-            return Ok(());
-        }
+            if from_str.is_empty() {
+                return Err(Error::Usage(msg::empty_from().to_string()));
+            }
 
-        print!("B: ");
-        io::stdout().flush()?;
-        let mut t_input = String::new();
-        io::stdin().read_line(&mut t_input)?;
-        to_str = t_input.trim().to_string();
+            ui::prompt("B: ")?;
+            let t_input = ui::read_line(tty)?;
+            to_str = t_input.trim().to_string();
+        }
     }
 
     // --- 3. 筛选文件 ---
     let pattern = Pattern::new(&pattern_str)?;
-    let matched_files: Vec<String> = all_files
-        .into_iter()
-        .filter(|file_name| pattern.matches(file_name))
-        .collect();
-
+    // 模板模式下 <FROM_STR> 只用于提供捕获组
+    let template = match &args.template {
+        Some(template) => {
+            let captures = (!from_str.is_empty())
+                .then(|| Regex::new(&from_str))
+                .transpose()?;
+            Some((Template::parse(template, captures.as_ref())?, captures))
+        }
+        None => None,
+    };
+    let replacer = if from_str.is_empty() || template.is_some() {
+        None
+    } else {
+        Some(Replacer::new(&from_str, &to_str, args.regex)?)
+    };
+    // 模板或位置参数给出的替换是第一步，显示中间名称时为各步骤编号
+    let mut descriptions = Vec::new();
+    if let Some(template) = &args.template {
+        descriptions.push(msg::template_line(template.cyan()));
+    }
+    descriptions.extend(replacer.as_ref().map(describe_replacer));
+    descriptions.extend(pipeline.steps.iter().map(|step| describe_step(step, args.case_scope)));
+    let numbered = args.show_steps && descriptions.len() > 1;
+    for (i, description) in descriptions.iter().enumerate() {
+        if numbered {
+            say!("{}. {}", i + 1, description);
+        } else {
+            say!("{}", description);
+        }
+    }
+    let matched_files = match_files(path, &all_files, &pattern, pattern_str.contains('/'), args.sort);
     if matched_files.is_empty() {
-        println!("\n没有文件匹配模式 '{}'", pattern_str);
-        return Ok(());
+        return Err(Error::NothingMatched(msg::no_match(&pattern_str)));
     }
+    ui::emit(&Record::Matches {
+        pattern: &pattern_str,
+        entries: &matched_files,
+    });
 
     // --- 4. 预览和确认 ---
-    println!("\n{}", "匹配到的文件及重命名预览:".bold());
+    let mut traces = Traces::new();
+    let new_names: Vec<String> = if args.edit {
+        editor::edit_names(&matched_files)?
+    } else if let Some((template, captures)) = &template {
+        let mut counter = Counter::new(args.start, args.step, args.reset_per_dir);
+        let mut names = Vec::with_capacity(matched_files.len());
+        for old in &matched_files {
+            let (parent, name) = plan::split_name(old);
+            let captures = match captures {
+                // 不匹配捕获正则的文件保持原名
+                Some(re) => match re.captures(name) {
+                    Some(caps) => Some(caps),
+                    None => {
+                        names.push(old.clone());
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            let ctx = template::Context {
+                root: path,
+                rel: old,
+                n: counter.next(old),
+                captures,
+            };
+            let mut steps = vec![template.render(&ctx)?];
+            steps.extend(pipeline.trace(&steps[0], None, ctx.n));
+            if args.show_steps {
+                traces.insert(old.clone(), changed_steps(name, &steps));
+            }
+            names.push(format!("{}{}", parent, steps.pop().unwrap_or_default()));
+        }
+        names
+    } else {
+        let traces = args.show_steps.then_some(&mut traces);
+        replace_names(&matched_files, replacer.as_ref(), &args, &pipeline, traces)
+    };
+    say!("\n{}", msg::match_preview_heading().bold());
     let renames: Vec<(String, String)> = matched_files
-        .iter()
-        .map(|old_name| (old_name.clone(), old_name.replace(&from_str, &to_str)))
+        .into_iter()
+        .zip(new_names)
         .filter(|(old, new)| old != new) // 只处理实际发生变化的文件
         .collect();
 
-    if renames.is_empty() {
-        println!("没有需要重命名的文件。");
-        return Ok(());
+    apply_plan(path, renames, &args, tty, args.yes, &traces)
+}
+
+/// 筛选匹配模式的条目并按指定顺序排列
+///
+/// `match_path` 为 true 时匹配相对路径，否则只匹配文件名。
+fn match_files(
+    path: &Path,
+    all_files: &[String],
+    pattern: &Pattern,
+    match_path: bool,
+    sort: SortOrder,
+) -> Vec<String> {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    let mut matched: Vec<String> = all_files
+        .iter()
+        .filter(|rel| {
+            let target = if match_path {
+                rel.replace(std::path::MAIN_SEPARATOR, "/")
+            } else {
+                plan::split_name(rel).1.to_string()
+            };
+            pattern.matches_with(&target, options)
+        })
+        .cloned()
+        .collect();
+    sequence::sort_entries(path, &mut matched, sort);
+    matched
+}
+
+/// 按替换规则生成新路径，只替换文件名部分，文件保留在原来的父目录中
+///
+/// `traces` 不为 `None` 时记录每个条目的中间文件名。
+fn replace_names(
+    matched: &[String],
+    replacer: Option<&Replacer>,
+    args: &Args,
+    pipeline: &Pipeline,
+    mut traces: Option<&mut Traces>,
+) -> Vec<String> {
+    let mut counter = Counter::new(args.start, args.step, args.reset_per_dir);
+    matched
+        .iter()
+        .map(|old| {
+            let (parent, name) = plan::split_name(old);
+            let steps = pipeline.trace(name, replacer, counter.next(old));
+            if let Some(traces) = traces.as_deref_mut() {
+                traces.insert(old.clone(), changed_steps(name, &steps));
+            }
+            format!("{}{}", parent, steps.last().map_or(name, String::as_str))
+        })
+        .collect()
+}
+
+fn describe_replacer(replacer: &Replacer) -> String {
+    match replacer {
+        Replacer::Literal { from, to } => msg::replace_line(from.cyan(), to.cyan()),
+        Replacer::Regex { re, to } => msg::replace_regex_line(re.as_str().cyan(), to.cyan()),
     }
+}
 
-    for (old, new) in &renames {
-        println!("{} {} {}", old.red(), "->".yellow(), new.green());
+/// 预览前显示的一个转换步骤
+fn describe_step(step: &Step, case_scope: Scope) -> String {
+    match step {
+        Step::Replace(replacer) => describe_replacer(replacer),
+        Step::Case(style) => msg::case_line(
+            format!("{:?}", style).to_lowercase().cyan(),
+            format!("{:?}", case_scope).to_lowercase(),
+        ),
+        Step::Ext(op) => msg::ext_line(op.describe().cyan()),
     }
+}
+
+/// 在全屏界面中反复调整模式和替换字符串，确认后执行选中的重命名
+fn run_tui(path: &Path, all_files: &[String], args: &Args, pipeline: &Pipeline) -> Result<(), Error> {
+    let fields = tui::Fields {
+        pattern: args.pattern.clone().unwrap_or_default(),
+        from: args.from_str.clone().unwrap_or_default(),
+        to: args.to_str.clone().unwrap_or_default(),
+    };
+    let build = |fields: &tui::Fields| -> Result<Vec<(String, String)>, Error> {
+        // 模式为空时显示全部条目
+        let pattern_str = if fields.pattern.is_empty() {
+            "*"
+        } else {
+            fields.pattern.as_str()
+        };
+        let pattern = Pattern::new(pattern_str)?;
+        let matched = match_files(path, all_files, &pattern, pattern_str.contains('/'), args.sort);
+        let replacer = if fields.from.is_empty() {
+            None
+        } else {
+            Some(Replacer::new(&fields.from, &fields.to, args.regex)?)
+        };
+        let new_names = replace_names(&matched, replacer.as_ref(), args, pipeline, None);
+        Ok(matched.into_iter().zip(new_names).collect())
+    };
+    let renames = tui::run(path, fields, all_files.len(), args.regex, args.on_conflict, &build)?;
+    say!("\n{}", msg::preview_heading().bold());
+    apply_plan(path, renames, args, false, true, &Traces::new())
+}
+
+/// 预览、检查冲突、确认并执行重命名计划，`confirmed` 为 true 时不再询问
+fn apply_plan(
+    path: &Path,
+    renames: Vec<(String, String)>,
+    args: &Args,
+    tty: bool,
+    confirmed: bool,
+    traces: &Traces,
+) -> Result<(), Error> {
+    let mut renames = renames;
+    // 预览中的编号，排除部分条目后其余条目保持原来的编号
+    let mut numbers: Vec<usize> = (1..=renames.len()).collect();
+    let total = renames.len();
+    // 解决冲突时已经逐个确认过的条目不再重复询问
+    let mut selected = false;
+    loop {
+        if renames.is_empty() {
+            say!("{}", msg::nothing_to_rename());
+            emit_summary(0, 0, None);
+            return Ok(());
+        }
+
+        let resolved = preview_and_resolve(
+            path,
+            renames.clone(),
+            &numbers,
+            args.on_conflict,
+            args.all,
+            traces,
+        );
+        let mut resolved = match resolved {
+            Ok(resolved) => resolved,
+            // 存在阻止执行的冲突时，允许排除误匹配的条目后重新检查，放弃时仍按冲突报错
+            Err(Error::Failure(reason)) if !confirmed => {
+                say!("\n{} {}", msg::error_label().red(), reason);
+                let kept = if args.interactive {
+                    selected = true;
+                    select::per_file(renames.clone(), tty)?.0
+                } else {
+                    let Some(skipped) = select::exclude(tty, total)? else {
+                        return Err(Error::Failure(reason));
+                    };
+                    renames
+                        .iter()
+                        .zip(&numbers)
+                        .filter(|(_, n)| !skipped.contains(n))
+                        .map(|(rename, _)| rename.clone())
+                        .collect()
+                };
+                numbers = kept.iter().map(|(old, _)| number_of(old, &renames, &numbers)).collect();
+                renames = kept;
+                say!("\n{}", msg::preview_heading().bold());
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if resolved.is_empty() {
+            say!("{}", msg::nothing_to_rename());
+            emit_summary(0, 0, None);
+            return Ok(());
+        }
+
+        if args.interactive && !selected {
+            let (kept, changed) = select::per_file(resolved, tty)?;
+            resolved = kept;
+            // 跳过或编辑了部分条目时，重新检查冲突并显示最终计划
+            if changed {
+                let numbers: Vec<usize> = resolved
+                    .iter()
+                    .map(|(old, _)| number_of(old, &renames, &numbers))
+                    .collect();
+                say!("\n{}", msg::preview_heading().bold());
+                resolved = preview_and_resolve(
+                    path,
+                    resolved,
+                    &numbers,
+                    args.on_conflict,
+                    args.all,
+                    traces,
+                )?;
+            }
+        }
 
-    let mut confirmation = String::new();
-    if !args.yes {
-        print!("\n是否继续? (y/N): ");
-        io::stdout().flush()?;
-        io::stdin().read_line(&mut confirmation)?;
+        if let Some(format) = args.export {
+            let steps = plan::order_renames(path, &resolved);
+            match &args.export_to {
+                Some(file) => {
+                    let mut out = io::BufWriter::new(fs::File::create(file)?);
+                    export::write(&mut out, format, path, &resolved, &steps)?;
+                    say!("{}", msg::exported(resolved.len(), file.display()));
+                }
+                None => export::write(&mut io::stdout().lock(), format, path, &resolved, &steps)?,
+            }
+            return Ok(());
+        }
+
+        // --- 5. 执行重命名 ---
+        if !confirmed && !args.interactive {
+            match select::confirm(tty, total)? {
+                Answer::Proceed => {}
+                Answer::Cancel => return Err(Error::Cancelled),
+                Answer::Skip(skipped) => {
+                    (renames, numbers) = renames
+                        .into_iter()
+                        .zip(numbers)
+                        .filter(|(_, n)| !skipped.contains(n))
+                        .unzip();
+                    say!("\n{}", msg::preview_heading().bold());
+                    continue;
+                }
+            }
+        }
+        let execution = execute(path, &resolved, args.keep_going);
+        return finish(path, execution);
     }
+}
 
-    // --- 5. 执行重命名 ---
-    if args.yes || confirmation.trim().to_lowercase() == "y" {
-        println!("\n开始执行重命名...");
-        for (old_name, new_name) in &renames {
-            let old_path = path.join(old_name);
-            let new_path = path.join(new_name);
-            match fs::rename(&old_path, &new_path) {
-                Ok(_) => println!("Renamed: {} -> {}", old_path.display(), new_path.display()),
-                Err(e) => eprintln!("Failed to rename {}: {}", old_path.display(), e),
+/// 条目在预览中的编号
+fn number_of(old: &str, renames: &[(String, String)], numbers: &[usize]) -> usize {
+    renames
+        .iter()
+        .zip(numbers)
+        .find(|((o, _), _)| o == old)
+        .map_or(0, |(_, n)| *n)
+}
+
+/// 显示重命名预览并在修改文件系统之前检查冲突，返回按策略处理后的计划
+///
+/// `numbers` 为每个条目在预览中显示的编号，可用于 `skip` 排除；
+/// `traces` 中记录了中间文件名的条目在下方逐步列出。
+fn preview_and_resolve(
+    path: &Path,
+    renames: Vec<(String, String)>,
+    numbers: &[usize],
+    on_conflict: OnConflict,
+    show_all: bool,
+    traces: &Traces,
+) -> Result<Vec<(String, String)>, Error> {
+    let conflicts = plan::find_conflicts(path, &renames);
+    for (old, new) in &renames {
+        if let Some(c) = conflicts.iter().find(|c| &c.old == old) {
+            ui::emit(&Record::Conflict {
+                old,
+                new,
+                kind: c.kind.id(),
+            });
+        }
+    }
+    let shown = preview_limit(renames.len(), show_all);
+    // 超出显示上限的条目只在存在冲突时显示
+    let rows: Vec<(usize, Diff, Option<&plan::Conflict>)> = renames
+        .iter()
+        .enumerate()
+        .map(|(i, (old, new))| (i, old, new, conflicts.iter().find(|c| &c.old == old)))
+        .filter(|(i, _, _, conflict)| *i < shown || conflict.is_some())
+        .map(|(i, old, new, conflict)| (i, Diff::new(old, new), conflict))
+        .collect();
+    let number_width = numbers.iter().max().map_or(1, |n| n.to_string().len());
+    let column = aligned_column(rows.iter().map(|(_, diff, _)| diff.old_width()));
+    for (i, diff, conflict) in &rows {
+        let (old, new) = &renames[*i];
+        let mut line = format!(
+            "{} {}",
+            format!("{:>number_width$}", numbers[*i]).dimmed(),
+            preview_line(diff, column)
+        );
+        match conflict {
+            Some(c) => line = format!("{} {}", line, msg::tag_conflict(c.kind).on_red()),
+            // 仅大小写不同的重命名容易被忽略，单独标出
+            None if old.to_lowercase() == new.to_lowercase() => {
+                line = format!("{} {}", line, msg::tag_case_only().cyan())
+            }
+            None => {}
+        }
+        say!("{}", line);
+        for (step, name) in traces.get(old).into_iter().flatten() {
+            say!("{:number_width$} {}", "", msg::step_trace(step, name).dimmed());
+        }
+    }
+    print_truncation_note(shown, renames.len());
+    let planned = renames.len();
+    let renames = plan::resolve_conflicts(path, renames, &conflicts, on_conflict)
+        .map_err(Error::Failure)?;
+    if !conflicts.is_empty() {
+        match on_conflict {
+            OnConflict::Skip => say!("{}", msg::skipped_conflicts(planned - renames.len())),
+            OnConflict::Suffix => {
+                say!("{}", msg::suffix_heading());
+                let diffs: Vec<Diff> = renames
+                    .iter()
+                    .filter(|(old, _)| conflicts.iter().any(|c| &c.old == old))
+                    .map(|(old, new)| Diff::new(old, new))
+                    .collect();
+                let column = aligned_column(diffs.iter().map(Diff::old_width));
+                for diff in &diffs {
+                    say!("{}", preview_line(diff, column));
+                }
             }
+            OnConflict::Overwrite => say!(
+                "{}",
+                msg::will_overwrite(msg::warning_label().yellow(), conflicts.len())
+            ),
+            OnConflict::Abort => {}
         }
-        println!("\n{} 重命名完成。", "Success:".green());
+    }
+    for (old, new) in &renames {
+        ui::emit(&Record::Plan { old, new });
+    }
+    Ok(renames)
+}
+
+/// 旧名称列的宽度：按最长的名称对齐，超过 [`MAX_COLUMN`] 的名称不参与对齐
+fn aligned_column(widths: impl Iterator<Item = usize>) -> usize {
+    widths.filter(|w| *w <= MAX_COLUMN).max().unwrap_or(0)
+}
+
+/// 一行预览：旧名称中删除的部分和新名称中插入的部分分别高亮，旧名称补齐到 `column` 列
+fn preview_line(diff: &Diff, column: usize) -> String {
+    format!(
+        "{}{} {} {}",
+        diff.old(),
+        " ".repeat(column.saturating_sub(diff.old_width())),
+        "->".yellow(),
+        diff.new_name()
+    )
+}
+
+fn preview_limit(total: usize, show_all: bool) -> usize {
+    if show_all {
+        total
     } else {
-        println!("操作已取消。");
+        total.min(PREVIEW_LIMIT)
     }
+}
 
-    Ok(())
+fn print_truncation_note(shown: usize, total: usize) {
+    if shown < total {
+        say!(
+            "{}",
+            msg::truncated(shown, total).dimmed()
+        );
+    }
 }
 
+fn confirm(tty: bool) -> io::Result<bool> {
+    ui::prompt(msg::confirm_prompt())?;
+    let confirmation = ui::read_line(tty)?;
+    Ok(confirmation.trim().to_lowercase() == "y")
+}
 
-fn list_files_in_dir(path: &Path) -> Result<Vec<String>, io::Error> {
-    let mut files = Vec::new();
-    for entry in fs::read_dir(path)? {
-        let entry = entry?;
-        if entry.file_type()?.is_file() {
-            if let Some(file_name) = entry.file_name().to_str() {
-                files.push(file_name.to_string());
+/// 一次批量执行的结果
+struct Execution {
+    /// 执行结束后仍然生效的步骤
+    applied: Vec<(String, String)>,
+    /// 失败的步骤数
+    failed: usize,
+}
+
+/// 按依赖顺序执行重命名
+///
+/// 默认以事务方式执行：遇到第一个失败即按相反顺序撤回本批次已完成的步骤。
+/// `keep_going` 为 true 时跳过失败的步骤继续执行。
+fn execute(path: &Path, renames: &[(String, String)], keep_going: bool) -> Execution {
+    say!("{}", msg::renaming());
+    let mut applied = Vec::new();
+    let mut failed = 0;
+    for (old_name, new_name) in plan::order_renames(path, renames) {
+        let old_path = path.join(&old_name);
+        let new_path = path.join(&new_name);
+        match fs::rename(&old_path, &new_path) {
+            Ok(_) => {
+                say!("{}", msg::renamed(old_path.display(), new_path.display()));
+                emit_result(&old_name, &new_name, "renamed", None);
+                applied.push((old_name, new_name));
+            }
+            Err(e) => {
+                if !ui::is_json() {
+                    eprintln!("{}", msg::rename_failed(old_path.display(), &e));
+                }
+                emit_result(&old_name, &new_name, "failed", Some(&e));
+                failed += 1;
+                if !keep_going {
+                    applied = rollback(path, applied);
+                    break;
+                }
+            }
+        }
+    }
+    Execution { applied, failed }
+}
+
+/// 按相反顺序撤回已执行的步骤，返回无法撤回、仍然生效的步骤
+fn rollback(path: &Path, applied: Vec<(String, String)>) -> Vec<(String, String)> {
+    say!("\n{}", msg::rolling_back().yellow());
+    let mut remaining = Vec::new();
+    for (old_name, new_name) in applied.into_iter().rev() {
+        let old_path = path.join(&old_name);
+        let new_path = path.join(&new_name);
+        match fs::rename(&new_path, &old_path) {
+            Ok(_) => {
+                say!("{}", msg::restored(new_path.display(), old_path.display()));
+                emit_result(&old_name, &new_name, "restored", None);
+            }
+            Err(e) => {
+                if !ui::is_json() {
+                    eprintln!("{}", msg::restore_failed(new_path.display(), &e));
+                }
+                emit_result(&old_name, &new_name, "restore_failed", Some(&e));
+                remaining.push((old_name, new_name));
             }
         }
-        if files.len() >= 50 {
+    }
+    remaining.reverse();
+    remaining
+}
+
+/// 输出单个步骤的执行结果记录，`old`/`new` 为该步骤正向的源和目标
+fn emit_result(old: &str, new: &str, status: &str, error: Option<&io::Error>) {
+    ui::emit(&Record::Result {
+        old,
+        new,
+        status,
+        error: error.map(|e| e.to_string()),
+    });
+}
+
+fn emit_summary(renamed: usize, failed: usize, batch: Option<u64>) {
+    ui::emit(&Record::Summary {
+        renamed,
+        failed,
+        batch,
+    });
+}
+
+/// 根据执行结果输出总结，写入撤销日志，并在存在失败时返回错误
+fn finish(path: &Path, execution: Execution) -> Result<(), Error> {
+    let Execution { applied, failed } = execution;
+    if failed == 0 {
+        say!("{}", msg::rename_done(msg::success_label().green()));
+    }
+    let batch = record_batch(path, &applied);
+    emit_summary(applied.len(), failed, batch);
+    if failed == 0 {
+        Ok(())
+    } else if applied.is_empty() {
+        Err(Error::Failure(msg::rolled_back().to_string()))
+    } else {
+        Err(Error::PartialFailure(msg::partial_failure(failed, applied.len())))
+    }
+}
+
+/// 写入撤销日志并返回批次 id；日志写入失败不影响已完成的重命名，仅给出警告
+fn record_batch(path: &Path, applied: &[(String, String)]) -> Option<u64> {
+    match journal::record(path, applied) {
+        Ok(Some(id)) => {
+            say!("{}", msg::batch_recorded(id));
+            Some(id)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            eprintln!("{}", msg::journal_failed(msg::warning_label().yellow(), e));
+            None
+        }
+    }
+}
+
+/// 命令行中直接给出的选项（长选项名），预设中的同名选项不再展开
+///
+/// 命令行中的选项可能依赖预设提供的选项（如 `--max-depth` 依赖 `--recursive`），
+/// 展开预设之前无法完整解析，因此直接扫描原始参数，错误留给展开之后的正式解析报告。
+fn given_options(argv: &[std::ffi::OsString]) -> Vec<String> {
+    let command = Args::command();
+    let takes_value = |arg: &clap::Arg| arg.get_action().takes_values();
+    let mut given = Vec::new();
+    let mut args = argv.iter().skip(1).map(|arg| arg.to_str());
+    while let Some(arg) = args.next() {
+        let Some(arg) = arg else { continue };
+        if arg == "--" {
             break;
         }
+        // 找到的选项，以及它的值是否已写在同一个参数中
+        let mut found = None;
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, inline) = long.split_once('=').map_or((long, false), |(name, _)| (name, true));
+            found = command
+                .get_arguments()
+                .find(|a| a.get_long() == Some(name))
+                .map(|a| (a, inline));
+        } else if let Some(shorts) = arg.strip_prefix('-') {
+            // 短选项可以连写（`-ey`），带值的短选项之后的部分是它的值
+            for (i, c) in shorts.char_indices() {
+                let Some(a) = command.get_arguments().find(|a| a.get_short() == Some(c)) else {
+                    break;
+                };
+                if takes_value(a) {
+                    found = Some((a, i + c.len_utf8() < shorts.len()));
+                    break;
+                }
+                given.extend(a.get_long().map(str::to_string));
+            }
+        }
+        if let Some((a, inline)) = found {
+            given.extend(a.get_long().map(str::to_string));
+            if takes_value(a) && !inline {
+                args.next();
+            }
+        }
+    }
+    given
+}
+
+/// 预设中可以使用的长选项名
+fn preset_options() -> Vec<String> {
+    Args::command()
+        .get_arguments()
+        .filter_map(|arg| arg.get_long())
+        .filter(|long| *long != "preset")
+        .map(str::to_string)
+        .collect()
+}
+
+fn presets() -> Result<(), Error> {
+    let presets = config::load()?;
+    if presets.is_empty() {
+        let paths: Vec<String> = config::config_paths()
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        say!("{}", msg::no_presets(paths.join(", ")));
+        return Ok(());
+    }
+    let longs = preset_options();
+    for (name, preset) in &presets {
+        // 有错误的预设照常列出，只在使用时才会中止
+        let (args, error) = match preset.args(name, &longs, &[]) {
+            Ok(args) => (args, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        ui::emit(&Record::Preset {
+            name,
+            description: preset.description.as_deref(),
+            source: &preset.source.to_string_lossy(),
+            pattern: preset.pattern.as_deref(),
+            args: &args,
+            error: error.clone(),
+        });
+        say!(
+            "{} {} {}",
+            name.cyan().bold(),
+            preset.description.as_deref().unwrap_or_default(),
+            format!("({})", preset.source.display()).dimmed()
+        );
+        match error {
+            Some(error) => say!("    {}", error.red()),
+            None => {
+                // 以可以直接粘贴到命令行的形式显示，筛选模式前补上 <PATH>
+                let safe = |c: char| c.is_ascii_alphanumeric() || "-_=.,/:+".contains(c);
+                let line: Vec<String> = preset
+                    .pattern
+                    .iter()
+                    .flat_map(|p| [".".to_string(), export::shell_quote(p)])
+                    .chain(args.iter().map(|arg| {
+                        if arg.chars().all(safe) {
+                            arg.clone()
+                        } else {
+                            export::shell_quote(arg)
+                        }
+                    }))
+                    .collect();
+                say!("    {}", line.join(" "));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn history(limit: usize) -> Result<(), Error> {
+    let batches = journal::load()?;
+    if batches.is_empty() {
+        say!("{}", msg::no_history());
+        return Ok(());
+    }
+    for batch in batches.iter().rev().take(limit) {
+        ui::emit(&Record::Batch {
+            id: batch.id,
+            timestamp: batch.timestamp.to_rfc3339(),
+            dir: &batch.dir.to_string_lossy(),
+            renames: &batch.renames,
+            undone: batch.undone,
+        });
+        let status = if batch.undone {
+            msg::undone_tag().dimmed()
+        } else {
+            "".normal()
+        };
+        say!(
+            "{}",
+            msg::history_line(
+                format!("#{}", batch.id).cyan(),
+                batch.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                batch.dir.display(),
+                batch.renames.len(),
+                status
+            )
+        );
+    }
+    Ok(())
+}
+
+fn undo(id: Option<u64>, yes: bool) -> Result<(), Error> {
+    let batches = journal::load()?;
+    let batch = match id {
+        Some(id) => batches
+            .iter()
+            .find(|b| b.id == id)
+            .ok_or_else(|| Error::Usage(msg::batch_not_found(id)))?,
+        None => batches
+            .iter()
+            .rev()
+            .find(|b| !b.undone)
+            .ok_or_else(|| Error::NothingMatched(msg::nothing_to_undo().to_string()))?,
+    };
+    if batch.undone {
+        return Err(Error::Usage(msg::batch_undone(batch.id)));
+    }
+
+    let path = batch.dir.as_path();
+    say!(
+        "{}",
+        msg::undo_heading(
+            batch.id,
+            batch.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            path.display()
+        )
+    );
+    let renames = batch.inverse();
+    // 撤销与正向重命名使用相同的冲突检查，任何冲突都会中止
+    let numbers: Vec<usize> = (1..=renames.len()).collect();
+    let renames = preview_and_resolve(
+        path,
+        renames,
+        &numbers,
+        OnConflict::Abort,
+        true,
+        &Traces::new(),
+    )?;
+
+    if yes || confirm(false)? {
+        let execution = execute(path, &renames, false);
+        if execution.failed == 0 {
+            journal::mark_undone(batch.id)?;
+            emit_summary(execution.applied.len(), 0, None);
+            say!("{}", msg::undo_done(msg::success_label().green(), batch.id));
+            Ok(())
+        } else {
+            finish(path, execution)
+        }
+    } else {
+        Err(Error::Cancelled)
     }
-    files.sort();
-    Ok(files)
 }
@@ -0,0 +1,154 @@
+use crate::error::Error;
+use crate::i18n::msg;
+use crate::plan::split_name;
+use clap::ValueEnum;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// 映射文件格式
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MappingFormat {
+    /// 逗号分隔，每行 `old,new`
+    #[value(help = msg::value_mapping_csv())]
+    Csv,
+    /// 制表符分隔，每行 `old<TAB>new`
+    #[value(help = msg::value_mapping_tsv())]
+    Tsv,
+    /// `[["old", "new"]]`、`[{"old": .., "new": ..}]` 或 `{"old": "new"}`
+    #[value(help = msg::value_mapping_json())]
+    Json,
+}
+
+impl MappingFormat {
+    /// 按扩展名推断格式，无法识别时按 CSV 处理
+    pub fn detect(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("json") => MappingFormat::Json,
+            Some("tsv" | "tab") => MappingFormat::Tsv,
+            _ => MappingFormat::Csv,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonMapping {
+    Pairs(Vec<(String, String)>),
+    Objects(Vec<JsonEntry>),
+    Map(JsonObject),
+}
+
+/// `{"old": "new"}` 形式的映射，按文件中的顺序保留全部条目，重复的旧名称留给后续检查报错
+struct JsonObject(Vec<(String, String)>);
+
+impl<'de> Deserialize<'de> for JsonObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Entries;
+
+        impl<'de> Visitor<'de> for Entries {
+            type Value = JsonObject;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object mapping old names to new names")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonObject, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(JsonObject(entries))
+            }
+        }
+
+        deserializer.deserialize_map(Entries)
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonEntry {
+    old: String,
+    new: String,
+}
+
+/// 读取映射文件，返回相对于根目录的 旧路径 -> 新路径
+///
+/// 新名称不含路径分隔符时视为与旧文件同目录的文件名。
+/// CSV/TSV 的首行为 `old,new` 或 `from,to` 时作为表头跳过。
+pub fn load(path: &Path, format: Option<MappingFormat>) -> Result<Vec<(String, String)>, Error> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| Error::Usage(msg::mapping_unreadable(path.display(), e)))?;
+    // 表格软件导出的 CSV 常带有 BOM
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let invalid = |e: &dyn std::fmt::Display| {
+        Error::Usage(msg::mapping_invalid(path.display(), e))
+    };
+
+    let pairs = match format.unwrap_or_else(|| MappingFormat::detect(path)) {
+        MappingFormat::Json => match serde_json::from_str(content).map_err(|e| invalid(&e))? {
+            JsonMapping::Pairs(pairs) => pairs,
+            JsonMapping::Objects(entries) => entries.into_iter().map(|e| (e.old, e.new)).collect(),
+            JsonMapping::Map(JsonObject(entries)) => entries,
+        },
+        format => {
+            let delimiter = if format == MappingFormat::Tsv {
+                b'\t'
+            } else {
+                b','
+            };
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .delimiter(delimiter)
+                .flexible(true)
+                .from_reader(content.as_bytes());
+            let mut pairs = Vec::new();
+            for (i, record) in reader.records().enumerate() {
+                let record = record.map_err(|e| invalid(&e))?;
+                if record.iter().all(|field| field.trim().is_empty()) {
+                    continue;
+                }
+                if record.len() != 2 {
+                    return Err(invalid(&msg::mapping_columns(i + 1, record.len())));
+                }
+                let is_header = i == 0
+                    && matches!(
+                        (
+                            record[0].trim().to_lowercase().as_str(),
+                            record[1].trim().to_lowercase().as_str()
+                        ),
+                        ("old", "new") | ("from", "to")
+                    );
+                if !is_header {
+                    pairs.push((record[0].to_string(), record[1].to_string()));
+                }
+            }
+            pairs
+        }
+    };
+
+    let mut seen = HashSet::new();
+    let mut renames = Vec::with_capacity(pairs.len());
+    for (old, new) in pairs {
+        if !seen.insert(old.clone()) {
+            return Err(invalid(&msg::mapping_duplicate(&old)));
+        }
+        let new = if new.contains(std::path::is_separator) {
+            new
+        } else {
+            format!("{}{}", split_name(&old).0, new)
+        };
+        if old != new {
+            renames.push((old, new));
+        }
+    }
+    Ok(renames)
+}
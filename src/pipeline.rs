@@ -0,0 +1,100 @@
+use crate::case::{self, CaseStyle};
+use crate::ext::{ExtOp, Scope, map_scope};
+use crate::sequence;
+use regex::{Captures, Regex};
+
+/// 文件名替换规则：字面量或正则表达式
+pub enum Replacer {
+    Literal { from: String, to: String },
+    Regex { re: Regex, to: String },
+}
+
+impl Replacer {
+    pub fn new(from: &str, to: &str, regex: bool) -> Result<Self, regex::Error> {
+        if regex {
+            Ok(Replacer::Regex {
+                re: Regex::new(from)?,
+                to: to.to_string(),
+            })
+        } else {
+            Ok(Replacer::Literal {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+        }
+    }
+
+    /// 对文件名执行替换，`first` 为 true 时只替换第一处匹配，`n` 为该文件的序号
+    pub fn apply(&self, name: &str, first: bool, n: u64) -> String {
+        match self {
+            Replacer::Literal { from, to } => {
+                let to = sequence::expand(to, n);
+                if first {
+                    name.replacen(from.as_str(), &to, 1)
+                } else {
+                    name.replace(from.as_str(), &to)
+                }
+            }
+            Replacer::Regex { re, to } => {
+                let expand = |caps: &Captures| sequence::expand_captures(to, caps, n);
+                if first {
+                    re.replace(name, expand).into_owned()
+                } else {
+                    re.replace_all(name, expand).into_owned()
+                }
+            }
+        }
+    }
+}
+
+/// 流水线中的一个操作
+pub enum Step {
+    /// `-r FROM=TO` 给出的替换
+    Replace(Replacer),
+    /// 大小写转换
+    Case(CaseStyle),
+    /// 扩展名操作
+    Ext(ExtOp),
+}
+
+/// 依次作用于文件名（不含父目录）的一组操作
+///
+/// 位置参数 `<FROM_STR>`/`<TO_STR>` 给出的替换总是最先执行，其余步骤按命令行中出现的顺序执行。
+pub struct Pipeline {
+    pub steps: Vec<Step>,
+    /// 替换时只替换第一处匹配
+    pub first: bool,
+    /// 替换作用的范围
+    pub scope: Scope,
+    /// 大小写转换作用的范围
+    pub case_scope: Scope,
+}
+
+impl Pipeline {
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// 依次执行 `main` 和各个步骤，返回每一步执行后的文件名
+    pub fn trace(&self, name: &str, main: Option<&Replacer>, n: u64) -> Vec<String> {
+        let mut names = Vec::with_capacity(self.steps.len() + 1);
+        let mut current = name.to_string();
+        if let Some(replacer) = main {
+            current = self.replace(&current, replacer, n);
+            names.push(current.clone());
+        }
+        for step in &self.steps {
+            current = match step {
+                Step::Replace(replacer) => self.replace(&current, replacer, n),
+                Step::Case(style) => case::convert(&current, *style, self.case_scope),
+                Step::Ext(op) => op.apply(&current),
+            };
+            names.push(current.clone());
+        }
+        names
+    }
+
+    fn replace(&self, name: &str, replacer: &Replacer, n: u64) -> String {
+        map_scope(name, self.scope, |part| replacer.apply(part, self.first, n))
+    }
+}
@@ -0,0 +1,592 @@
+use crate::ext::split_ext;
+use crate::i18n::msg;
+use clap::ValueEnum;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+/// 遇到冲突时的处理策略
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnConflict {
+    /// 报告全部冲突并中止，不执行任何重命名
+    #[default]
+    #[value(help = msg::value_abort())]
+    Abort,
+    /// 跳过存在冲突的条目
+    #[value(help = msg::value_skip())]
+    Skip,
+    /// 为冲突的新名称追加数字后缀，如 `a_1.txt`
+    #[value(help = msg::value_suffix())]
+    Suffix,
+    /// 覆盖磁盘上已存在的目标文件（计划内的重复目标仍会中止）
+    #[value(help = msg::value_overwrite())]
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// 多个文件将被重命名为同一个名称
+    Duplicate,
+    /// 目标名称在磁盘上已存在，且不会在本次计划中被移走
+    Exists,
+    /// 新名称为空、包含路径分隔符或会移动到其他目录
+    Invalid,
+    /// 源文件不存在
+    Missing,
+}
+
+impl ConflictKind {
+    /// 机器可读的稳定标识，用于 JSON 输出
+    pub fn id(self) -> &'static str {
+        match self {
+            ConflictKind::Duplicate => "duplicate",
+            ConflictKind::Exists => "exists",
+            ConflictKind::Invalid => "invalid",
+            ConflictKind::Missing => "missing",
+        }
+    }
+}
+
+impl fmt::Display for ConflictKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ConflictKind::Duplicate => msg::conflict_duplicate(),
+            ConflictKind::Exists => msg::conflict_exists(),
+            ConflictKind::Invalid => msg::conflict_invalid(),
+            ConflictKind::Missing => msg::conflict_missing(),
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub old: String,
+    pub kind: ConflictKind,
+}
+
+/// 检查重命名计划中的所有冲突，不修改文件系统
+pub fn find_conflicts(dir: &Path, renames: &[(String, String)]) -> Vec<Conflict> {
+    let key = name_key(dir, renames);
+    let mut conflicts = Vec::new();
+    let sources: HashSet<Cow<'_, str>> = renames.iter().map(|(old, _)| key(old)).collect();
+    // 重名的每个条目都算作冲突，而不是让排在前面的那个保留目标名称
+    // 源文件是否存在只检查一次，两轮之间磁盘发生变化也不会得到不一致的结果
+    let present: Vec<bool> = renames.iter().map(|(old, _)| exists(dir, old)).collect();
+    let mut targets: HashMap<Cow<'_, str>, usize> = HashMap::new();
+    for ((old, new), &present) in renames.iter().zip(&present) {
+        if present && is_valid_target(old, new) {
+            *targets.entry(key(new)).or_default() += 1;
+        }
+    }
+    for ((old, new), &present) in renames.iter().zip(&present) {
+        let kind = if !present {
+            Some(ConflictKind::Missing)
+        } else if !is_valid_target(old, new) {
+            Some(ConflictKind::Invalid)
+        } else if targets[&key(new)] > 1 {
+            Some(ConflictKind::Duplicate)
+        } else if exists(dir, new) && !sources.contains(&key(new)) {
+            Some(ConflictKind::Exists)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            conflicts.push(Conflict {
+                old: old.clone(),
+                kind,
+            });
+        }
+    }
+    conflicts
+}
+
+/// 按策略处理冲突，返回可安全执行的重命名计划
+///
+/// `Abort` 策略下只要存在冲突就返回错误；`Suffix`/`Overwrite` 无法处理的冲突同样报错。
+pub fn resolve_conflicts(
+    dir: &Path,
+    renames: Vec<(String, String)>,
+    conflicts: &[Conflict],
+    policy: OnConflict,
+) -> Result<Vec<(String, String)>, String> {
+    if conflicts.is_empty() {
+        return Ok(renames);
+    }
+    let blocking = conflicts
+        .iter()
+        .filter(|c| match policy {
+            OnConflict::Abort => true,
+            OnConflict::Skip => false,
+            OnConflict::Suffix => matches!(c.kind, ConflictKind::Invalid | ConflictKind::Missing),
+            OnConflict::Overwrite => c.kind != ConflictKind::Exists,
+        })
+        .count();
+    if blocking > 0 {
+        return Err(msg::conflicts_found(blocking));
+    }
+
+    let conflicting: HashSet<&str> = conflicts.iter().map(|c| c.old.as_str()).collect();
+    match policy {
+        OnConflict::Abort | OnConflict::Overwrite => Ok(renames),
+        OnConflict::Skip => {
+            // 跳过某个文件后，它原本要腾出的名称不再空闲，需要反复检查直到没有冲突
+            let mut renames: Vec<_> = renames
+                .into_iter()
+                .filter(|(old, _)| !conflicting.contains(old.as_str()))
+                .collect();
+            loop {
+                let conflicts = find_conflicts(dir, &renames);
+                if conflicts.is_empty() {
+                    return Ok(renames);
+                }
+                let conflicting: HashSet<&str> = conflicts.iter().map(|c| c.old.as_str()).collect();
+                renames.retain(|(old, _)| !conflicting.contains(old.as_str()));
+            }
+        }
+        OnConflict::Suffix => {
+            let key = name_key(dir, &renames);
+            let mut taken: HashSet<String> = renames
+                .iter()
+                .filter(|(old, _)| !conflicting.contains(old.as_str()))
+                .map(|(_, new)| key(new).into_owned())
+                .collect();
+            Ok(renames
+                .iter()
+                .map(|(old, new)| {
+                    if !conflicting.contains(old.as_str()) {
+                        return (old.clone(), new.clone());
+                    }
+                    let new = with_free_suffix(dir, new, |candidate| {
+                        taken.contains(key(candidate).as_ref())
+                    });
+                    taken.insert(key(&new).into_owned());
+                    (old.clone(), new)
+                })
+                .collect())
+        }
+    }
+}
+
+/// 将计划排列为可依次执行的步骤
+///
+/// 链式重命名（a→b, b→c）按依赖倒序执行，先腾出目标名称；
+/// 环形重命名（a→b, b→a）借助临时名称打破循环。
+/// 较深的路径先于其父目录执行，保证批次执行期间路径始终有效。
+/// 调用前计划中的目标名称必须互不相同。
+pub fn order_renames(dir: &Path, renames: &[(String, String)]) -> Vec<(String, String)> {
+    order_by_key(dir, renames, name_key(dir, renames))
+}
+
+/// 目标与待处理的源文件按与冲突检查相同的键比较，大小写不敏感时 a→B 依赖于 b 先被移走
+fn order_by_key(
+    dir: &Path,
+    renames: &[(String, String)],
+    key: fn(&str) -> Cow<'_, str>,
+) -> Vec<(String, String)> {
+    // 链条只会出现在同一父目录内，因此按深度降序处理即可先子后父
+    let mut starts: Vec<&str> = renames.iter().map(|(old, _)| old.as_str()).collect();
+    starts.sort_by_key(|old| std::cmp::Reverse(depth(old)));
+
+    let targets: HashMap<&str, &str> = renames
+        .iter()
+        .map(|(old, new)| (old.as_str(), new.as_str()))
+        .collect();
+    let sources: HashMap<Cow<'_, str>, &str> = renames
+        .iter()
+        .map(|(old, _)| (key(old), old.as_str()))
+        .collect();
+    let mut pending: HashSet<&str> = targets.keys().copied().collect();
+    let mut steps = Vec::with_capacity(renames.len());
+    let mut tmp_counter = 0;
+
+    for start in starts {
+        if !pending.contains(start) {
+            continue;
+        }
+        // 沿着链条前进，直到目标不再是待处理的源文件，或回到起点
+        let mut chain = vec![start];
+        let is_cycle = loop {
+            let dst = key(targets[chain.last().unwrap()]);
+            if dst == key(start) {
+                break true;
+            }
+            match sources.get(&dst) {
+                Some(next) if pending.contains(next) => chain.push(next),
+                _ => break false,
+            }
+        };
+        for src in &chain {
+            pending.remove(src);
+        }
+
+        // 从链尾开始执行，每一步的目标都已被腾出；环形时起点先移到临时名称
+        let tmp = is_cycle.then(|| temp_name(dir, start, renames, &mut tmp_counter));
+        if let Some(tmp) = &tmp {
+            steps.push((start.to_string(), tmp.clone()));
+        }
+        for (i, src) in chain.iter().enumerate().rev() {
+            let dst = targets[chain[i]];
+            let src = match (&tmp, i) {
+                (Some(tmp), 0) => tmp.as_str(),
+                _ => src,
+            };
+            // 大小写不敏感的文件系统上直接改变大小写可能无效或报错，经临时名称中转
+            if is_case_only(src, dst) {
+                let tmp = temp_name(dir, src, renames, &mut tmp_counter);
+                steps.push((src.to_string(), tmp.clone()));
+                steps.push((tmp, dst.to_string()));
+            } else {
+                steps.push((src.to_string(), dst.to_string()));
+            }
+        }
+    }
+    steps
+}
+
+/// 在 `near` 所在目录中生成磁盘与计划中均未占用的临时名称
+fn temp_name(dir: &Path, near: &str, renames: &[(String, String)], counter: &mut usize) -> String {
+    let (parent, _) = split_name(near);
+    loop {
+        *counter += 1;
+        let name = format!(
+            "{}.rename-cli-tmp-{}-{}",
+            parent,
+            std::process::id(),
+            counter
+        );
+        if !exists(dir, &name)
+            && !renames
+                .iter()
+                .any(|(old, new)| *old == name || *new == name)
+        {
+            return name;
+        }
+    }
+}
+
+/// 生成 `stem_N.ext` 形式的、磁盘与计划中（`taken` 返回 true）均未占用的名称
+fn with_free_suffix(dir: &Path, path: &str, taken: impl Fn(&str) -> bool) -> String {
+    let (parent, name) = split_name(path);
+    let (stem, ext) = split_ext(name);
+    let ext = if ext.is_empty() {
+        String::new()
+    } else {
+        format!(".{}", ext)
+    };
+    (1..)
+        .map(|n| format!("{}{}_{}{}", parent, stem, n, ext))
+        .find(|candidate| !taken(candidate) && !exists(dir, candidate))
+        .expect("suffix space exhausted")
+}
+
+/// 将相对路径拆分为父目录前缀（含末尾分隔符）和文件名
+pub fn split_name(path: &str) -> (&str, &str) {
+    match path.rfind(std::path::is_separator) {
+        Some(i) => path.split_at(i + 1),
+        None => ("", path),
+    }
+}
+
+fn depth(path: &str) -> usize {
+    path.chars().filter(|&c| std::path::is_separator(c)).count()
+}
+
+/// 新名称必须是合法的文件名，且与旧名称位于同一目录
+fn is_valid_target(old: &str, new: &str) -> bool {
+    let (old_parent, _) = split_name(old);
+    new.strip_prefix(old_parent).is_some_and(is_valid_name)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.chars().any(std::path::is_separator)
+}
+
+/// 比较名称时使用的键：大小写不敏感的文件系统上，仅大小写不同的名称指向同一个文件
+fn name_key(dir: &Path, renames: &[(String, String)]) -> fn(&str) -> Cow<'_, str> {
+    if is_case_insensitive(dir, renames) {
+        |name| Cow::Owned(name.to_lowercase())
+    } else {
+        |name| Cow::Borrowed(name)
+    }
+}
+
+fn is_case_only(old: &str, new: &str) -> bool {
+    old != new && old.to_lowercase() == new.to_lowercase()
+}
+
+/// 探测 `dir` 所在的文件系统是否大小写不敏感
+///
+/// 取计划中第一个含有字母的源文件，检查其大小写互换后的名称是否指向同一个文件，
+/// 不在文件系统上创建任何探测文件。
+pub fn is_case_insensitive(dir: &Path, renames: &[(String, String)]) -> bool {
+    renames.iter().find_map(|(old, _)| {
+        let (parent, name) = split_name(old);
+        let swapped: String = name
+            .chars()
+            .map(|c| {
+                if c.is_lowercase() {
+                    c.to_uppercase().next().unwrap_or(c)
+                } else {
+                    c.to_lowercase().next().unwrap_or(c)
+                }
+            })
+            .collect();
+        (swapped != name)
+            .then(|| same_file(&dir.join(old), &dir.join(format!("{}{}", parent, swapped))))
+    }) == Some(true)
+}
+
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (a.symlink_metadata(), b.symlink_metadata()) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// 非 Unix 平台上规范化路径会返回磁盘上的实际大小写
+#[cfg(not(unix))]
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 使用 `symlink_metadata`，悬空的符号链接同样视为已存在
+fn exists(dir: &Path, name: &str) -> bool {
+    dir.join(name).symlink_metadata().is_ok()
+}
+
+/// 测试中用字面量构造重命名计划
+#[cfg(test)]
+pub fn plan(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(old, new)| (old.to_string(), new.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 不存在的目录：磁盘上没有任何文件，临时名称只需避开计划中的名称
+    const NOWHERE: &str = "/nonexistent/rename-cli-test";
+
+    /// 系统临时目录中只包含 `files` 的目录，离开作用域时删除
+    struct Scratch(std::path::PathBuf);
+
+    impl Scratch {
+        fn new(test: &str, files: &[&str]) -> Self {
+            let dir = std::env::temp_dir().join(format!("rename-cli-test-{}-{}", std::process::id(), test));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            for file in files {
+                std::fs::write(dir.join(file), "").unwrap();
+            }
+            Scratch(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn kinds(dir: &Path, renames: &[(String, String)]) -> Vec<(String, ConflictKind)> {
+        find_conflicts(dir, renames)
+            .into_iter()
+            .map(|c| (c.old, c.kind))
+            .collect()
+    }
+
+    #[test]
+    fn every_member_of_a_duplicate_group_conflicts() {
+        let scratch = Scratch::new("duplicate", &["a", "b", "c"]);
+        let renames = plan(&[("a", "x"), ("b", "x"), ("c", "y")]);
+        assert_eq!(
+            kinds(&scratch.0, &renames),
+            [("a".to_string(), ConflictKind::Duplicate), ("b".to_string(), ConflictKind::Duplicate)]
+        );
+    }
+
+    #[test]
+    fn existing_target_conflicts_unless_it_is_moved_away() {
+        let scratch = Scratch::new("exists", &["a", "b", "c"]);
+        assert_eq!(
+            kinds(&scratch.0, &plan(&[("a", "c")])),
+            [("a".to_string(), ConflictKind::Exists)]
+        );
+        assert!(kinds(&scratch.0, &plan(&[("a", "b"), ("b", "c"), ("c", "a")])).is_empty());
+    }
+
+    #[test]
+    fn invalid_and_missing_entries() {
+        let scratch = Scratch::new("invalid", &["a", "b"]);
+        let renames = plan(&[("a", "d/x"), ("b", ".."), ("ghost", "y")]);
+        assert_eq!(
+            kinds(&scratch.0, &renames),
+            [
+                ("a".to_string(), ConflictKind::Invalid),
+                ("b".to_string(), ConflictKind::Invalid),
+                ("ghost".to_string(), ConflictKind::Missing),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_source_does_not_make_a_duplicate() {
+        let scratch = Scratch::new("missing", &["a"]);
+        let renames = plan(&[("a", "x"), ("ghost", "x")]);
+        assert_eq!(
+            kinds(&scratch.0, &renames),
+            [("ghost".to_string(), ConflictKind::Missing)]
+        );
+    }
+
+    #[test]
+    fn abort_and_overwrite_refuse_blocking_conflicts() {
+        let scratch = Scratch::new("abort", &["a", "b", "c"]);
+        let exists = plan(&[("a", "c")]);
+        let conflicts = find_conflicts(&scratch.0, &exists);
+        assert!(resolve_conflicts(&scratch.0, exists.clone(), &conflicts, OnConflict::Abort).is_err());
+        assert_eq!(
+            resolve_conflicts(&scratch.0, exists.clone(), &conflicts, OnConflict::Overwrite),
+            Ok(exists)
+        );
+        let duplicate = plan(&[("a", "x"), ("b", "x")]);
+        let conflicts = find_conflicts(&scratch.0, &duplicate);
+        assert!(resolve_conflicts(&scratch.0, duplicate, &conflicts, OnConflict::Overwrite).is_err());
+    }
+
+    #[test]
+    fn skip_drops_entries_whose_target_is_no_longer_freed() {
+        // 跳过 b -> x 后 b 仍在原处，a -> b 也随之冲突
+        let scratch = Scratch::new("skip", &["a", "b", "c", "x"]);
+        let renames = plan(&[("a", "b"), ("b", "x"), ("c", "d")]);
+        let conflicts = find_conflicts(&scratch.0, &renames);
+        assert_eq!(
+            resolve_conflicts(&scratch.0, renames, &conflicts, OnConflict::Skip),
+            Ok(plan(&[("c", "d")]))
+        );
+    }
+
+    #[test]
+    fn suffix_avoids_names_on_disk_and_in_the_plan() {
+        let scratch = Scratch::new("suffix", &["a.txt", "b.txt", "c.txt", "c_1.txt", "d.txt"]);
+        let renames = plan(&[("a.txt", "c.txt"), ("b.txt", "c.txt"), ("d.txt", "c_2.txt")]);
+        let conflicts = find_conflicts(&scratch.0, &renames);
+        assert_eq!(
+            resolve_conflicts(&scratch.0, renames, &conflicts, OnConflict::Suffix),
+            Ok(plan(&[("a.txt", "c_3.txt"), ("b.txt", "c_4.txt"), ("d.txt", "c_2.txt")]))
+        );
+    }
+
+    fn exact(name: &str) -> Cow<'_, str> {
+        Cow::Borrowed(name)
+    }
+
+    fn folded(name: &str) -> Cow<'_, str> {
+        Cow::Owned(name.to_lowercase())
+    }
+
+    /// 在内存中依次执行步骤：源路径必须存在，目标不得被其他条目占用，重命名目录时其子路径随之移动
+    fn simulate(files: &[&str], steps: &[(String, String)], key: fn(&str) -> Cow<'_, str>) -> Vec<String> {
+        let mut entries: Vec<String> = files.iter().map(|f| f.to_string()).collect();
+        for (src, dst) in steps {
+            assert!(entries.contains(src), "{} does not exist before {} -> {}", src, src, dst);
+            assert!(
+                !entries.iter().any(|e| e != src && key(e) == key(dst)),
+                "{} is still taken before {} -> {}",
+                dst,
+                src,
+                dst
+            );
+            for entry in &mut entries {
+                if entry == src {
+                    *entry = dst.clone();
+                } else if let Some(rest) = entry.strip_prefix(&format!("{}/", src)) {
+                    *entry = format!("{}/{}", dst, rest);
+                }
+            }
+        }
+        entries.sort();
+        entries
+    }
+
+    fn sorted(names: &[&str]) -> Vec<String> {
+        let mut names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn swap_goes_through_a_temp_name() {
+        let renames = plan(&[("a", "b"), ("b", "a")]);
+        let steps = order_by_key(Path::new(NOWHERE), &renames, exact);
+        assert_eq!(steps.len(), 3);
+        let tmp = &steps[0].1;
+        assert!(tmp.contains(".rename-cli-tmp-"));
+        assert_eq!(steps, plan(&[("a", tmp), ("b", "a"), (tmp, "b")]));
+        assert_eq!(simulate(&["a", "b"], &steps, exact), sorted(&["a", "b"]));
+    }
+
+    #[test]
+    fn three_cycle() {
+        let renames = plan(&[("a", "b"), ("b", "c"), ("c", "a")]);
+        let steps = order_by_key(Path::new(NOWHERE), &renames, exact);
+        assert_eq!(steps.len(), 4);
+        assert_eq!(simulate(&["a", "b", "c"], &steps, exact), sorted(&["a", "b", "c"]));
+    }
+
+    #[test]
+    fn shift_by_one_chain_runs_from_the_end() {
+        let pairs: Vec<(String, String)> = (1..20)
+            .map(|i| (format!("{:02}", i), format!("{:02}", i + 1)))
+            .collect();
+        let steps = order_by_key(Path::new(NOWHERE), &pairs, exact);
+        // 无需临时名称，从 19 -> 20 开始逐个后移
+        assert_eq!(steps.len(), 19);
+        assert_eq!(steps[0], ("19".to_string(), "20".to_string()));
+        assert_eq!(steps[18], ("01".to_string(), "02".to_string()));
+        let files: Vec<String> = (1..20).map(|i| format!("{:02}", i)).collect();
+        let files: Vec<&str> = files.iter().map(String::as_str).collect();
+        let expected: Vec<String> = (2..=20).map(|i| format!("{:02}", i)).collect();
+        assert_eq!(simulate(&files, &steps, exact), expected);
+    }
+
+    #[test]
+    fn child_is_renamed_before_its_parent() {
+        let renames = plan(&[("a", "b"), ("a/x", "a/y")]);
+        let steps = order_by_key(Path::new(NOWHERE), &renames, exact);
+        assert_eq!(steps, plan(&[("a/x", "a/y"), ("a", "b")]));
+        assert_eq!(simulate(&["a", "a/x"], &steps, exact), sorted(&["b", "b/y"]));
+    }
+
+    #[test]
+    fn case_only_rename_goes_through_a_temp_name() {
+        let renames = plan(&[("a.txt", "A.txt")]);
+        let steps = order_by_key(Path::new(NOWHERE), &renames, folded);
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].0, "a.txt");
+        assert_eq!(steps[1].1, "A.txt");
+        assert_eq!(simulate(&["a.txt"], &steps, folded), sorted(&["A.txt"]));
+    }
+
+    #[test]
+    fn case_insensitive_chain_frees_the_folded_target_first() {
+        // B.txt 与 b.txt 是同一个文件，b.txt 必须先移走
+        let renames = plan(&[("a.txt", "B.txt"), ("b.txt", "c.txt")]);
+        let steps = order_by_key(Path::new(NOWHERE), &renames, folded);
+        assert_eq!(steps, plan(&[("b.txt", "c.txt"), ("a.txt", "B.txt")]));
+        assert_eq!(
+            simulate(&["a.txt", "b.txt"], &steps, folded),
+            sorted(&["B.txt", "c.txt"])
+        );
+    }
+}
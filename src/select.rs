@@ -0,0 +1,142 @@
+use crate::error::Error;
+use crate::i18n::msg;
+use crate::plan::split_name;
+use crate::ui::{self, say};
+use colored::*;
+use std::collections::BTreeSet;
+
+/// 整体确认的回答
+pub enum Answer {
+    Proceed,
+    Cancel,
+    /// 从计划中排除这些编号（从 1 开始）的重命名后重新预览
+    Skip(BTreeSet<usize>),
+}
+
+/// 询问是否执行整个计划，或用 `skip 3-7,12` 排除部分编号
+pub fn confirm(tty: bool, max: usize) -> Result<Answer, Error> {
+    loop {
+        ui::prompt(msg::confirm_skip_prompt())?;
+        let line = ui::read_line(tty)?;
+        let answer = line.trim();
+        if let Some(spec) = answer.strip_prefix("skip") {
+            match parse_ranges(spec, max) {
+                Ok(indices) => return Ok(Answer::Skip(indices)),
+                Err(reason) => say!("{}", msg::invalid_selection(spec.trim(), reason).red()),
+            }
+        } else if answer.eq_ignore_ascii_case("y") {
+            return Ok(Answer::Proceed);
+        } else {
+            return Ok(Answer::Cancel);
+        }
+    }
+}
+
+/// 存在阻止执行的冲突时询问要排除的编号，不排除时返回 `None`
+pub fn exclude(tty: bool, max: usize) -> Result<Option<BTreeSet<usize>>, Error> {
+    loop {
+        ui::prompt(msg::exclude_prompt())?;
+        let line = ui::read_line(tty)?;
+        let Some(spec) = line.trim().strip_prefix("skip") else {
+            return Ok(None);
+        };
+        match parse_ranges(spec, max) {
+            Ok(indices) => return Ok(Some(indices)),
+            Err(reason) => say!("{}", msg::invalid_selection(spec.trim(), reason).red()),
+        }
+    }
+}
+
+/// 解析 `3-7,12` 形式的编号列表，编号必须在 1..=max 之内
+pub fn parse_ranges(spec: &str, max: usize) -> Result<BTreeSet<usize>, String> {
+    let mut indices = BTreeSet::new();
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=max).contains(n))
+            .ok_or_else(|| msg::index_out_of_range(s.trim(), max))
+    };
+    for part in spec.split([',', ' ']).filter(|p| !p.trim().is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(msg::reversed_range(part.trim()));
+                }
+                indices.extend(start..=end);
+            }
+            None => {
+                indices.insert(parse(part)?);
+            }
+        }
+    }
+    if indices.is_empty() {
+        return Err(msg::empty_selection().to_string());
+    }
+    Ok(indices)
+}
+
+/// 逐个询问每个重命名，返回选中的重命名以及计划是否被修改
+///
+/// y 执行，n 跳过，a 执行此项及其余全部，q 跳过此项及其余全部，e 编辑新名称。
+pub fn per_file(
+    renames: Vec<(String, String)>,
+    tty: bool,
+) -> Result<(Vec<(String, String)>, bool), Error> {
+    let total = renames.len();
+    let mut selected = Vec::with_capacity(total);
+    let mut edited = false;
+    let mut remaining = renames.into_iter();
+    while let Some((old, new)) = remaining.next() {
+        loop {
+            ui::prompt(&format!(
+                "{} {} {} {} ",
+                old.red(),
+                "->".yellow(),
+                new.green(),
+                "[y,n,a,q,e,?]".bold()
+            ))?;
+            let line = ui::read_line(tty)?;
+            // 输入结束时视为取消，避免在管道中无限询问
+            if line.is_empty() {
+                return Err(Error::Cancelled);
+            }
+            match line.trim().to_lowercase().as_str() {
+                "y" => {
+                    selected.push((old, new));
+                }
+                "n" => {}
+                "a" => {
+                    selected.push((old, new));
+                    selected.extend(remaining.by_ref());
+                }
+                "q" => {
+                    remaining.by_ref().for_each(drop);
+                }
+                "e" => {
+                    let (parent, name) = split_name(&new);
+                    ui::prompt(&msg::edit_name_prompt(name))?;
+                    let input = ui::read_line(tty)?;
+                    let input = input.trim_end_matches(['\r', '\n']);
+                    if input.is_empty() || input == name {
+                        selected.push((old, new));
+                    } else {
+                        selected.push((old, format!("{}{}", parent, input)));
+                        edited = true;
+                    }
+                }
+                _ => {
+                    say!("{}", msg::per_file_help());
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+    if selected.is_empty() {
+        return Err(Error::Cancelled);
+    }
+    let changed = edited || selected.len() != total;
+    Ok((selected, changed))
+}
@@ -0,0 +1,216 @@
+use crate::i18n::msg;
+use crate::plan::split_name;
+use clap::ValueEnum;
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+/// 替换字符串中的序号占位符：`{n}`、`{n:3}`（空格补齐）、`{n:03}`（补零）
+static COUNTER_TOKEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{n(?::(\d+))?\}").unwrap());
+
+/// 编号时文件的排列顺序
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// 按路径的字典序
+    #[default]
+    #[value(help = msg::value_sort_name())]
+    Name,
+    /// 按路径的自然顺序，数字按数值比较（file2 < file10）
+    #[value(help = msg::value_sort_natural())]
+    Natural,
+    /// 按修改时间，从旧到新
+    #[value(help = msg::value_sort_mtime())]
+    Mtime,
+    /// 按文件大小，从小到大
+    #[value(help = msg::value_sort_size())]
+    Size,
+}
+
+/// 按指定顺序排列 `root` 下的相对路径
+pub fn sort_entries(root: &Path, entries: &mut [String], order: SortOrder) {
+    match order {
+        SortOrder::Name => entries.sort(),
+        SortOrder::Natural => entries.sort_by(|a, b| natural_cmp(a, b)),
+        SortOrder::Mtime => entries.sort_by_cached_key(|rel| {
+            let mtime = root
+                .join(rel)
+                .symlink_metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (mtime, rel.clone())
+        }),
+        SortOrder::Size => entries.sort_by_cached_key(|rel| {
+            let size = root.join(rel).symlink_metadata().map_or(0, |m| m.len());
+            (size, rel.clone())
+        }),
+    }
+}
+
+/// 自然排序：连续的数字按数值比较，其余字符按字典序比较
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (da, ra) = split_digits(a);
+                let (db, rb) = split_digits(b);
+                let (ta, tb) = (da.trim_start_matches('0'), db.trim_start_matches('0'));
+                let ord = ta
+                    .len()
+                    .cmp(&tb.len())
+                    .then_with(|| ta.cmp(tb))
+                    .then_with(|| da.len().cmp(&db.len()));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                (a, b) = (ra, rb);
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            }
+        }
+    }
+}
+
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// 序号生成器，可按目录分别计数
+pub struct Counter {
+    start: u64,
+    step: u64,
+    reset_per_dir: bool,
+    next: HashMap<String, u64>,
+}
+
+impl Counter {
+    pub fn new(start: u64, step: u64, reset_per_dir: bool) -> Self {
+        Counter {
+            start,
+            step,
+            reset_per_dir,
+            next: HashMap::new(),
+        }
+    }
+
+    /// 返回 `path` 的序号并推进计数
+    pub fn next(&mut self, path: &str) -> u64 {
+        let key = if self.reset_per_dir {
+            split_name(path).0
+        } else {
+            ""
+        };
+        let n = self.next.entry(key.to_string()).or_insert(self.start);
+        let current = *n;
+        *n += self.step;
+        current
+    }
+}
+
+/// 将模板中的序号占位符替换为 `n`
+pub fn expand(template: &str, n: u64) -> Cow<'_, str> {
+    COUNTER_TOKEN.replace_all(template, |caps: &Captures| {
+        format_number(n, caps.get(1).map(|spec| spec.as_str()))
+    })
+}
+
+/// 正则替换时按序号占位符分段展开：捕获组引用交给 `Captures::expand`，序号直接写入结果
+///
+/// 先展开序号再交给正则会把 `$1{n}` 变成 `$11`；`${n}` 仍表示名为 `n` 的捕获组。
+pub fn expand_captures(template: &str, caps: &Captures, n: u64) -> String {
+    let mut result = String::new();
+    let mut last = 0;
+    for token in COUNTER_TOKEN.captures_iter(template) {
+        let whole = token.get(0).unwrap();
+        // 前面紧跟奇数个 `$` 时是 `${name}` 形式的捕获组引用（`$$` 为字面量 `$`）
+        let before = &template[..whole.start()];
+        let dollars = before.len() - before.trim_end_matches('$').len();
+        if dollars % 2 == 1 {
+            continue;
+        }
+        caps.expand(&template[last..whole.start()], &mut result);
+        result.push_str(&format_number(n, token.get(1).map(|spec| spec.as_str())));
+        last = whole.end();
+    }
+    caps.expand(&template[last..], &mut result);
+    result
+}
+
+/// 按宽度格式化序号：`3` 以空格补齐，`03` 以零补齐
+pub fn format_number(n: u64, spec: Option<&str>) -> String {
+    let spec = spec.unwrap_or("");
+    let width = spec.parse().unwrap_or(0);
+    if spec.starts_with('0') {
+        format!("{:0width$}", n, width = width)
+    } else {
+        format!("{:width$}", n, width = width)
+    }
+}
+
+/// 检查序号格式是否合法
+pub fn is_valid_spec(spec: &str) -> bool {
+    spec.parse::<usize>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(pattern: &str, template: &str, name: &str, n: u64) -> String {
+        Regex::new(pattern)
+            .unwrap()
+            .replace(name, |caps: &Captures| expand_captures(template, caps, n))
+            .into_owned()
+    }
+
+    #[test]
+    fn expand_formats_counter_tokens() {
+        assert_eq!(expand("img_{n}", 7), "img_7");
+        assert_eq!(expand("img_{n:3}", 7), "img_  7");
+        assert_eq!(expand("img_{n:03}", 7), "img_007");
+        assert_eq!(expand("{n}-{n:02}", 12), "12-12");
+        assert_eq!(expand("no tokens", 1), "no tokens");
+    }
+
+    #[test]
+    fn counter_after_group_reference_stays_separate() {
+        assert_eq!(replace(r"IMG_(\d+)_(\d+)", "$2-$1{n}", "IMG_1_2.jpg", 5), "2-15.jpg");
+        assert_eq!(replace(r"(\w+)\.txt", "${1}_{n:03}.txt", "notes.txt", 4), "notes_004.txt");
+    }
+
+    #[test]
+    fn braced_group_named_n_is_not_a_counter() {
+        assert_eq!(replace(r"(?P<n>\w+)\.txt", "${n}_{n}.txt", "notes.txt", 3), "notes_3.txt");
+        // `$$` 是字面量 `$`，其后的 `{n}` 仍是序号
+        assert_eq!(replace(r"(\w+)\.txt", "$${n}.txt", "notes.txt", 3), "$3.txt");
+    }
+
+    #[test]
+    fn natural_cmp_compares_digit_runs_by_value() {
+        assert_eq!(natural_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("file10", "file9"), Ordering::Greater);
+        assert_eq!(natural_cmp("a1b2", "a1b10"), Ordering::Less);
+        assert_eq!(natural_cmp("file", "file1"), Ordering::Less);
+        assert_eq!(natural_cmp("b1", "a2"), Ordering::Greater);
+        assert_eq!(natural_cmp("same", "same"), Ordering::Equal);
+    }
+
+    #[test]
+    fn natural_cmp_orders_leading_zeros_after_equal_values() {
+        assert_eq!(natural_cmp("x007", "x7"), Ordering::Greater);
+        assert_eq!(natural_cmp("x007", "x8"), Ordering::Less);
+        assert_eq!(natural_cmp("x0", "x00"), Ordering::Less);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::i18n::msg;
+use clap::ValueEnum;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// 交互输入所用的终端设备，在标准输入被文件列表占用时使用
+pub const TERMINAL: &str = if cfg!(windows) { "CONIN$" } else { "/dev/tty" };
+
+/// 可被重命名的条目类型
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryType {
+    /// 普通文件
+    #[value(name = "f", alias = "file", help = msg::value_file())]
+    File,
+    /// 目录
+    #[value(name = "d", alias = "dir", help = msg::value_dir())]
+    Dir,
+    /// 符号链接
+    #[value(name = "l", alias = "symlink", help = msg::value_symlink())]
+    Symlink,
+}
+
+/// 列出目录下指定类型的条目，返回相对于 `root` 的路径并排序
+///
+/// `max_depth` 为 1 时只列出 `root` 下的直接条目；不跟随指向目录的符号链接。
+pub fn list_files_in_dir(
+    root: &Path,
+    max_depth: usize,
+    types: &[EntryType],
+) -> Result<Vec<String>, io::Error> {
+    let mut files = Vec::new();
+    let mut dirs = vec![(root.to_path_buf(), 1)];
+    while let Some((dir, depth)) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() && depth < max_depth {
+                dirs.push((entry.path(), depth + 1));
+            }
+            if types.contains(&entry_type(&file_type))
+                && let Ok(rel) = entry.path().strip_prefix(root)
+                && let Some(rel) = rel.to_str()
+            {
+                files.push(rel.to_string());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 从文件或标准输入（`-`）读取路径列表，按换行或 NUL 分隔
+///
+/// 相对路径基于 `root`，绝对路径保持不变；重复的路径只保留一个。
+/// 不存在的路径会被保留，以便在冲突检查时报告。
+pub fn read_file_list(
+    list: &Path,
+    null: bool,
+    root: &Path,
+    types: &[EntryType],
+) -> Result<Vec<String>, io::Error> {
+    let mut content = Vec::new();
+    if list == Path::new("-") {
+        io::stdin().read_to_end(&mut content)?;
+    } else {
+        fs::File::open(list)?.read_to_end(&mut content)?;
+    }
+
+    let delimiter = if null { b'\0' } else { b'\n' };
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    for item in content.split(|&b| b == delimiter) {
+        let item = if null {
+            item
+        } else {
+            item.strip_suffix(b"\r").unwrap_or(item)
+        };
+        if item.is_empty() {
+            continue;
+        }
+        let Ok(item) = std::str::from_utf8(item) else {
+            eprintln!("{}", msg::non_utf8_path(String::from_utf8_lossy(item)));
+            continue;
+        };
+        // 目录可能以分隔符结尾，去掉后才能拆分出目录名
+        let rel = item.trim_end_matches(std::path::is_separator);
+        let rel = rel.strip_prefix("./").unwrap_or(rel);
+        if rel.is_empty() || !seen.insert(rel.to_string()) {
+            continue;
+        }
+        let keep = match root.join(rel).symlink_metadata() {
+            Ok(metadata) => types.contains(&entry_type(&metadata.file_type())),
+            Err(_) => true,
+        };
+        if keep {
+            files.push(rel.to_string());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn entry_type(file_type: &fs::FileType) -> EntryType {
+    if file_type.is_symlink() {
+        EntryType::Symlink
+    } else if file_type.is_dir() {
+        EntryType::Dir
+    } else {
+        EntryType::File
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::Error;
+use crate::ext::split_ext;
+use crate::i18n::msg;
+use crate::plan::split_name;
+use crate::sequence;
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, Local};
+use regex::{Captures, Regex};
+use std::fmt::Write;
+use std::path::Path;
+
+/// `{mtime}` 未指定格式时使用的日期格式
+const DEFAULT_DATE_FORMAT: &str = "%Y%m%d";
+
+/// 新名称模板，如 `{mtime:%Y-%m-%d}_{stem|slug}.{ext|lower}`
+///
+/// 占位符为 `{token[:arg][|filter]...}`，`{{`、`}}` 表示字面的花括号。
+/// 可用的 token：`name`、`stem`、`ext`、`parent`、`mtime[:格式]`、`size`、`n[:03]`，
+/// 以及 `<FROM_STR>` 正则的捕获组（`{1}`、`{year}`）。
+/// 过滤器：`lower`、`upper`、`slug`、`pad(N)`。
+#[derive(Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug)]
+enum Part {
+    Literal(String),
+    Token {
+        name: String,
+        arg: Option<String>,
+        filters: Vec<Filter>,
+    },
+}
+
+#[derive(Debug)]
+enum Filter {
+    Lower,
+    Upper,
+    Slug,
+    /// 左侧补零到指定宽度
+    Pad(usize),
+}
+
+/// 渲染单个文件名所需的上下文
+pub struct Context<'a> {
+    pub root: &'a Path,
+    /// 相对于 `root` 的路径
+    pub rel: &'a str,
+    pub n: u64,
+    pub captures: Option<Captures<'a>>,
+}
+
+const BUILTIN_TOKENS: [&str; 7] = ["name", "stem", "ext", "parent", "mtime", "size", "n"];
+
+impl Template {
+    /// 解析模板；`captures` 为提供捕获组的正则，用于校验捕获组引用
+    pub fn parse(s: &str, captures: Option<&Regex>) -> Result<Self, Error> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut body = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => body.push(c),
+                            None => {
+                                return Err(Error::Usage(msg::template_unclosed(s)));
+                            }
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(parse_token(&body, captures)?);
+                }
+                '}' => return Err(Error::Usage(msg::template_stray_close(s))),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Template { parts })
+    }
+
+    /// 按上下文渲染出新的文件名
+    pub fn render(&self, ctx: &Context) -> Result<String, Error> {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(s) => out.push_str(s),
+                Part::Token { name, arg, filters } => {
+                    let value = token_value(name, arg.as_deref(), ctx)?;
+                    out.push_str(&filters.iter().fold(value, |v, f| f.apply(v)));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn parse_token(body: &str, captures: Option<&Regex>) -> Result<Part, Error> {
+    let mut segments = body.split('|');
+    let head = segments.next().unwrap_or("").trim();
+    let (name, arg) = match head.split_once(':') {
+        Some((name, arg)) => (name.trim(), Some(arg.to_string())),
+        None => (head, None),
+    };
+
+    let is_capture = captures.is_some_and(|re| {
+        name.parse::<usize>().is_ok_and(|i| i < re.captures_len())
+            || re.capture_names().flatten().any(|n| n == name)
+    });
+    if !BUILTIN_TOKENS.contains(&name) && !is_capture {
+        return Err(Error::Usage(msg::template_unknown(name)));
+    }
+    match (name, arg.as_deref()) {
+        ("mtime", Some(fmt)) if StrftimeItems::new(fmt).parse().is_err() => {
+            return Err(Error::Usage(msg::invalid_date_format(fmt)));
+        }
+        ("n", Some(spec)) if !sequence::is_valid_spec(spec) => {
+            return Err(Error::Usage(msg::invalid_number_spec(spec)));
+        }
+        _ => {}
+    }
+
+    let filters = segments
+        .map(|f| parse_filter(f.trim()))
+        .collect::<Result<_, _>>()?;
+    Ok(Part::Token {
+        name: name.to_string(),
+        arg,
+        filters,
+    })
+}
+
+fn parse_filter(s: &str) -> Result<Filter, Error> {
+    match s {
+        "lower" => Ok(Filter::Lower),
+        "upper" => Ok(Filter::Upper),
+        "slug" => Ok(Filter::Slug),
+        _ => s
+            .strip_prefix("pad(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .and_then(|width| width.trim().parse().ok())
+            .map(Filter::Pad)
+            .ok_or_else(|| Error::Usage(msg::unknown_filter(s))),
+    }
+}
+
+impl Filter {
+    fn apply(&self, value: String) -> String {
+        match self {
+            Filter::Lower => value.to_lowercase(),
+            Filter::Upper => value.to_uppercase(),
+            Filter::Slug => slugify(&value),
+            Filter::Pad(width) => format!("{:0>width$}", value, width = *width),
+        }
+    }
+}
+
+fn token_value(name: &str, arg: Option<&str>, ctx: &Context) -> Result<String, Error> {
+    let (parent, file_name) = split_name(ctx.rel);
+    let (stem, ext) = split_ext(file_name);
+    let value = match name {
+        "name" => file_name.to_string(),
+        "stem" => stem.to_string(),
+        "ext" => ext.to_string(),
+        "parent" => {
+            let dir = ctx.root.join(parent);
+            let dir = dir.canonicalize().unwrap_or(dir);
+            dir.file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        }
+        "mtime" => {
+            let mtime: DateTime<Local> = ctx
+                .root
+                .join(ctx.rel)
+                .symlink_metadata()?
+                .modified()?
+                .into();
+            let mut out = String::new();
+            write!(out, "{}", mtime.format(arg.unwrap_or(DEFAULT_DATE_FORMAT)))
+                .map_err(|_| Error::Usage(msg::invalid_date_format(arg.unwrap_or(""))))?;
+            out
+        }
+        "size" => ctx.root.join(ctx.rel).symlink_metadata()?.len().to_string(),
+        "n" => sequence::format_number(ctx.n, arg),
+        _ => {
+            let caps = ctx.captures.as_ref();
+            let group = match name.parse::<usize>() {
+                Ok(i) => caps.and_then(|c| c.get(i)),
+                Err(_) => caps.and_then(|c| c.name(name)),
+            };
+            group.map_or("", |m| m.as_str()).to_string()
+        }
+    };
+    Ok(value)
+}
+
+/// 转为小写，非字母数字的字符序列替换为单个 `-`
+fn slugify(s: &str) -> String {
+    let mut slug = String::new();
+    for c in s.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
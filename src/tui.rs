@@ -0,0 +1,416 @@
+use crate::error::Error;
+use crate::i18n::msg;
+use crate::plan::{self, ConflictKind, OnConflict};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+/// 翻页时移动的行数
+const PAGE: isize = 10;
+
+/// 界面中可编辑的字段
+pub struct Fields {
+    pub pattern: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// 按字段生成 (旧路径, 新路径)，包括名称没有变化的匹配条目
+pub type Build<'a> = dyn Fn(&Fields) -> Result<Vec<(String, String)>, Error> + 'a;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Pattern,
+    From,
+    To,
+    List,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::Pattern => Focus::From,
+            Focus::From => Focus::To,
+            Focus::To => Focus::List,
+            Focus::List => Focus::Pattern,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            Focus::Pattern => Focus::List,
+            Focus::From => Focus::Pattern,
+            Focus::To => Focus::From,
+            Focus::List => Focus::To,
+        }
+    }
+}
+
+struct Row {
+    old: String,
+    new: String,
+    conflict: Option<ConflictKind>,
+}
+
+impl Row {
+    fn changed(&self) -> bool {
+        self.old != self.new
+    }
+}
+
+struct App<'a> {
+    root: &'a Path,
+    build: &'a Build<'a>,
+    fields: Fields,
+    regex: bool,
+    on_conflict: OnConflict,
+    /// 参与筛选的条目总数
+    total: usize,
+    focus: Focus,
+    /// 当前字段中光标所在的字符位置
+    cursor: usize,
+    rows: Vec<Row>,
+    /// 被排除的旧路径，修改字段后仍然保留
+    excluded: HashSet<String>,
+    /// 模式或正则无效时的错误
+    error: Option<String>,
+    /// 确认失败时的提示
+    status: Option<String>,
+    list: ListState,
+}
+
+/// 运行全屏界面，确认后返回选中的重命名；按 Esc 取消
+pub fn run(
+    root: &Path,
+    fields: Fields,
+    total: usize,
+    regex: bool,
+    on_conflict: OnConflict,
+    build: &Build,
+) -> Result<Vec<(String, String)>, Error> {
+    if !io::stdout().is_terminal() {
+        return Err(Error::Usage(msg::tui_needs_terminal().to_string()));
+    }
+    let mut app = App {
+        root,
+        build,
+        cursor: fields.pattern.chars().count(),
+        fields,
+        regex,
+        on_conflict,
+        total,
+        focus: Focus::Pattern,
+        rows: Vec::new(),
+        excluded: HashSet::new(),
+        error: None,
+        status: None,
+        list: ListState::default(),
+    };
+    app.refresh();
+    let mut terminal = ratatui::try_init()?;
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<Vec<(String, String)>, Error> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && let Some(result) = self.handle_key(key)
+            {
+                return result;
+            }
+        }
+    }
+
+    /// 按当前字段重新生成预览
+    fn refresh(&mut self) {
+        self.status = None;
+        match (self.build)(&self.fields) {
+            Ok(pairs) => {
+                self.error = None;
+                self.rows = pairs
+                    .into_iter()
+                    .map(|(old, new)| Row {
+                        old,
+                        new,
+                        conflict: None,
+                    })
+                    .collect();
+            }
+            Err(e) => {
+                // 正则错误包含多行说明，状态栏只有一行
+                self.error = Some(e.to_string().split_whitespace().collect::<Vec<_>>().join(" "));
+                self.rows.clear();
+            }
+        }
+        self.check_conflicts();
+        let selected = self.list.selected().unwrap_or(0);
+        self.list.select(match self.rows.len() {
+            0 => None,
+            len => Some(selected.min(len - 1)),
+        });
+    }
+
+    /// 名称发生变化且没有被排除的重命名
+    fn selected(&self) -> Vec<(String, String)> {
+        self.rows
+            .iter()
+            .filter(|row| row.changed() && !self.excluded.contains(&row.old))
+            .map(|row| (row.old.clone(), row.new.clone()))
+            .collect()
+    }
+
+    /// 只在选中的重命名之间检查冲突，排除某一行即可消除它引起的冲突
+    fn check_conflicts(&mut self) {
+        let conflicts = plan::find_conflicts(self.root, &self.selected());
+        for row in &mut self.rows {
+            row.conflict = conflicts
+                .iter()
+                .find(|c| c.old == row.old)
+                .map(|c| c.kind);
+        }
+    }
+
+    /// 返回 `Some` 时结束界面
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Result<Vec<(String, String)>, Error>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Some(Err(Error::Cancelled)),
+            KeyCode::Char('c') if ctrl => return Some(Err(Error::Cancelled)),
+            KeyCode::Enter => return self.confirm(),
+            KeyCode::Tab => self.set_focus(self.focus.next()),
+            KeyCode::BackTab => self.set_focus(self.focus.prev()),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-PAGE),
+            KeyCode::PageDown => self.move_selection(PAGE),
+            _ if self.focus == Focus::List => self.handle_list_key(key),
+            _ => {
+                let field = match self.focus {
+                    Focus::Pattern => &mut self.fields.pattern,
+                    Focus::From => &mut self.fields.from,
+                    Focus::To => &mut self.fields.to,
+                    Focus::List => return None,
+                };
+                if edit_field(field, &mut self.cursor, key) {
+                    self.refresh();
+                }
+            }
+        }
+        None
+    }
+
+    fn handle_list_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(' ') => {
+                let Some(row) = self.list.selected().and_then(|i| self.rows.get(i)) else {
+                    return;
+                };
+                if row.changed() && !self.excluded.remove(&row.old) {
+                    self.excluded.insert(row.old.clone());
+                }
+            }
+            KeyCode::Char('a') => {
+                // 有任何选中的行时全部排除，否则全部选中
+                if self.selected().is_empty() {
+                    self.excluded.clear();
+                } else {
+                    self.excluded
+                        .extend(self.rows.iter().filter(|r| r.changed()).map(|r| r.old.clone()));
+                }
+            }
+            KeyCode::Home => self.list.select_first(),
+            KeyCode::End => self.list.select_last(),
+            _ => return,
+        }
+        self.status = None;
+        self.check_conflicts();
+    }
+
+    fn set_focus(&mut self, focus: Focus) {
+        self.focus = focus;
+        self.cursor = match focus {
+            Focus::Pattern => self.fields.pattern.chars().count(),
+            Focus::From => self.fields.from.chars().count(),
+            Focus::To => self.fields.to.chars().count(),
+            Focus::List => 0,
+        };
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let current = self.list.selected().unwrap_or(0) as isize;
+        let last = self.rows.len() as isize - 1;
+        self.list.select(Some((current + delta).clamp(0, last) as usize));
+    }
+
+    /// 选中的重命名能按冲突策略执行时结束界面，否则在状态栏说明原因
+    fn confirm(&mut self) -> Option<Result<Vec<(String, String)>, Error>> {
+        if self.error.is_some() {
+            return None;
+        }
+        let selected = self.selected();
+        if selected.is_empty() {
+            self.status = Some(msg::tui_nothing_selected().to_string());
+            return None;
+        }
+        let conflicts = plan::find_conflicts(self.root, &selected);
+        match plan::resolve_conflicts(self.root, selected.clone(), &conflicts, self.on_conflict) {
+            Ok(_) => Some(Ok(selected)),
+            Err(e) => {
+                self.status = Some(e);
+                None
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [inputs, list, status, help] = Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        // --- 输入字段 ---
+        let from_label = if self.regex {
+            msg::tui_from_regex()
+        } else {
+            msg::tui_from()
+        };
+        let fields = [
+            (Focus::Pattern, msg::tui_pattern(), &self.fields.pattern),
+            (Focus::From, from_label, &self.fields.from),
+            (Focus::To, msg::tui_to(), &self.fields.to),
+        ];
+        let label_width = fields
+            .iter()
+            .map(|(_, label, _)| Span::raw(*label).width())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<Line> = fields
+            .iter()
+            .map(|(focus, label, value)| {
+                let padding = " ".repeat(label_width - Span::raw(*label).width() + 1);
+                let label = if *focus == self.focus {
+                    Span::raw(*label).yellow().bold()
+                } else {
+                    Span::raw(*label)
+                };
+                Line::from(vec![label, Span::raw(padding), Span::raw(value.as_str())])
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" rename-cli ")),
+            inputs,
+        );
+        if let Some(i) = fields.iter().position(|(focus, _, _)| *focus == self.focus) {
+            let before: String = fields[i].2.chars().take(self.cursor).collect();
+            let x = inputs.x as usize + 1 + label_width + 1 + Span::raw(before).width();
+            frame.set_cursor_position((x as u16, inputs.y + 1 + i as u16));
+        }
+
+        // --- 预览列表 ---
+        let items: Vec<ListItem> = self.rows.iter().map(|row| self.row_item(row)).collect();
+        let changed = self.rows.iter().filter(|r| r.changed()).count();
+        let title = msg::tui_list_title(self.selected().len(), changed, self.rows.len(), self.total);
+        let mut block = Block::bordered().title(title);
+        if self.focus == Focus::List {
+            block = block.border_style(Style::new().yellow());
+        }
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::new().reversed()),
+            list,
+            &mut self.list,
+        );
+
+        // --- 状态栏和按键说明 ---
+        let conflicts = self.rows.iter().filter(|r| r.conflict.is_some()).count();
+        let status_line = match (&self.error, &self.status) {
+            (Some(error), _) => Span::raw(error.as_str()).red(),
+            (None, Some(status)) => Span::raw(status.as_str()).yellow(),
+            (None, None) if conflicts > 0 => Span::raw(msg::tui_conflicts(conflicts)).red(),
+            (None, None) => Span::raw(""),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+        frame.render_widget(Paragraph::new(msg::tui_help()).dim(), help);
+    }
+
+    fn row_item(&self, row: &Row) -> ListItem<'static> {
+        if !row.changed() {
+            return ListItem::new(Line::from(format!("    {}", row.old)).dim());
+        }
+        let excluded = self.excluded.contains(&row.old);
+        let mut spans = vec![
+            Span::raw(if excluded { "[ ] " } else { "[x] " }),
+            Span::raw(row.old.clone()).red(),
+            Span::raw(" -> ").yellow(),
+            Span::raw(row.new.clone()).green(),
+        ];
+        if let Some(kind) = row.conflict {
+            spans.push(Span::raw(" "));
+            spans.push(Span::raw(msg::tag_conflict(kind)).white().on_red());
+        }
+        let line = Line::from(spans);
+        ListItem::new(if excluded { line.dim() } else { line })
+    }
+}
+
+/// 在光标处编辑单行文本，返回内容是否发生变化
+fn edit_field(field: &mut String, cursor: &mut usize, key: KeyEvent) -> bool {
+    let len = field.chars().count();
+    let byte = |s: &str, i: usize| s.char_indices().nth(i).map_or(s.len(), |(b, _)| b);
+    match key.code {
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            field.clear();
+            *cursor = 0;
+            true
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            field.insert(byte(field, *cursor), c);
+            *cursor += 1;
+            true
+        }
+        KeyCode::Backspace if *cursor > 0 => {
+            *cursor -= 1;
+            field.remove(byte(field, *cursor));
+            true
+        }
+        KeyCode::Delete if *cursor < len => {
+            field.remove(byte(field, *cursor));
+            true
+        }
+        KeyCode::Left => {
+            *cursor = cursor.saturating_sub(1);
+            false
+        }
+        KeyCode::Right => {
+            *cursor = (*cursor + 1).min(len);
+            false
+        }
+        KeyCode::Home => {
+            *cursor = 0;
+            false
+        }
+        KeyCode::End => {
+            *cursor = len;
+            false
+        }
+        _ => false,
+    }
+}
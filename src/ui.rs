@@ -0,0 +1,143 @@
+use crate::i18n::msg;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 输出格式
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// 面向人阅读的彩色文本
+    #[default]
+    #[value(help = msg::value_output_human())]
+    Human,
+    /// 每行一个 JSON 记录（NDJSON），字段名保持稳定
+    #[value(help = msg::value_output_json())]
+    Json,
+}
+
+const HUMAN: u8 = 0;
+const HUMAN_STDERR: u8 = 1;
+const JSON: u8 = 2;
+
+static MODE: AtomicU8 = AtomicU8::new(HUMAN);
+
+pub fn set_format(format: OutputFormat) {
+    let mode = match format {
+        OutputFormat::Human => HUMAN,
+        OutputFormat::Json => JSON,
+    };
+    MODE.store(mode, Ordering::Relaxed);
+}
+
+/// 将面向用户的提示改为输出到标准错误，使标准输出只包含导出的内容
+pub fn redirect_to_stderr() {
+    let _ = MODE.compare_exchange(HUMAN, HUMAN_STDERR, Ordering::Relaxed, Ordering::Relaxed);
+}
+
+pub fn to_stderr() -> bool {
+    MODE.load(Ordering::Relaxed) == HUMAN_STDERR
+}
+
+pub fn is_json() -> bool {
+    MODE.load(Ordering::Relaxed) == JSON
+}
+
+/// 输出一行面向用户的提示，用法同 `println!`；JSON 模式下不输出
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::ui::is_json() {
+        } else if $crate::ui::to_stderr() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use say;
+
+/// 输出不换行的输入提示并立即刷新；JSON 模式下提示写到标准错误
+pub fn prompt(text: &str) -> io::Result<()> {
+    if to_stderr() || is_json() {
+        eprint!("{}", text);
+        io::stderr().flush()
+    } else {
+        print!("{}", text);
+        io::stdout().flush()
+    }
+}
+
+/// 读取一行用户输入，`tty` 为 true 时从终端而不是标准输入读取
+pub fn read_line(tty: bool) -> io::Result<String> {
+    let mut line = String::new();
+    if tty {
+        let terminal = fs::File::open(crate::source::TERMINAL)?;
+        io::BufReader::new(terminal).read_line(&mut line)?;
+    } else {
+        io::stdin().read_line(&mut line)?;
+    }
+    Ok(line)
+}
+
+/// JSON 模式下输出的记录，`type` 字段区分记录种类
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record<'a> {
+    /// 列出的全部候选条目
+    Listing { root: &'a str, entries: &'a [String] },
+    /// 通过筛选模式的条目
+    Matches { pattern: &'a str, entries: &'a [String] },
+    /// 计划中检测到的冲突
+    Conflict { old: &'a str, new: &'a str, kind: &'a str },
+    /// 按冲突策略处理后的最终计划
+    Plan { old: &'a str, new: &'a str },
+    /// 单个步骤的执行结果：renamed、failed、restored 或 restore_failed
+    Result {
+        old: &'a str,
+        new: &'a str,
+        status: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// 批次执行完毕后的汇总
+    Summary {
+        renamed: usize,
+        failed: usize,
+        batch: Option<u64>,
+    },
+    /// 撤销日志中的一个批次
+    Batch {
+        id: u64,
+        timestamp: String,
+        dir: &'a str,
+        renames: &'a [(String, String)],
+        undone: bool,
+    },
+    /// 配置文件中定义的预设，`args` 为展开后的命令行参数；预设无效时给出 `error`
+    Preset {
+        name: &'a str,
+        description: Option<&'a str>,
+        source: &'a str,
+        pattern: Option<&'a str>,
+        args: &'a [String],
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// 导致进程以非零退出码结束的错误
+    Error {
+        kind: &'a str,
+        message: String,
+        exit_code: i32,
+    },
+}
+
+/// JSON 模式下把记录作为一行输出到标准输出
+pub fn emit(record: &Record) {
+    if is_json() {
+        match serde_json::to_string(record) {
+            Ok(line) => println!("{}", line),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}